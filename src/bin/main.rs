@@ -1,14 +1,47 @@
-use cavestory::{game::Game, input::Input};
-use cavestory::{game::*, GameResult};
-use sdl2::{event::Event, image::InitFlag, keyboard::Scancode, EventPump, Sdl, VideoSubsystem};
+use cavestory::game::*;
+use cavestory::graphics::{Graphics, Renderer};
+use cavestory::{
+    game::Game,
+    input::{Action, ActionMap, Controllers, Input, InputReplay},
+    sound::SoundEffects,
+};
+use sdl2::{event::Event, image::InitFlag, EventPump, Sdl, VideoSubsystem};
+use std::io::ErrorKind;
+use std::path::Path;
 use std::time::Duration;
 
+/// `--record <파일>`: 입력을 파일로 기록한다.
+/// `--replay <파일>`: 기록된 입력을 다시 재생한다.
+fn get_argument(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1).cloned())
+}
+
 fn main() {
     let context: Sdl = sdl2::init().unwrap();
     let video: VideoSubsystem = context.video().unwrap();
     let mut event_pump: EventPump = context.event_pump().unwrap();
-    let mut input: Input = Input::default();
+
+    // 키 설정 파일이 없으면 기본 키 설정을 저장해서 고칠 수 있게 한다.
+    // 파일이 잘못되었으면 사용자의 설정을 덮어쓰지 않고 이번에만 기본 키 설정을 쓴다.
+    let controls_path = Path::new(CONTROLS_PATH);
+    let bindings = match ActionMap::from_file(controls_path) {
+        Ok(bindings) => bindings,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            let bindings = ActionMap::default();
+            if let Err(e) = bindings.save(controls_path) {
+                eprintln!("warning: cannot save {}: {}", CONTROLS_PATH, e);
+            }
+            bindings
+        }
+        Err(e) => {
+            eprintln!("warning: cannot read {}: {}, using default controls", CONTROLS_PATH, e);
+            ActionMap::default()
+        }
+    };
+    let mut input: Input = Input::new(bindings);
     let timer = context.timer().unwrap();
+    let mut controllers = Controllers::new(context.game_controller().unwrap());
 
     let window: sdl2::video::Window = video
         .window("Cave Story", SCREEN_WIDTH, SCREEN_HEIGHT)
@@ -17,23 +50,56 @@ fn main() {
         .build()
         .unwrap();
 
-    let mut canvas = window.into_canvas().build().unwrap();
-    let texture_creator = canvas.texture_creator();
-
     // add PNG / JPEG support
     let _image_context = sdl2::image::init(InitFlag::PNG | InitFlag::JPG);
+    let ttf_context = sdl2::ttf::init().unwrap();
+
+    // 오디오 장치가 없으면 소리 없이 실행한다.
+    let audio = context.audio().and_then(|audio| {
+        sdl2::mixer::open_audio(
+            sdl2::mixer::DEFAULT_FREQUENCY,
+            sdl2::mixer::DEFAULT_FORMAT,
+            sdl2::mixer::DEFAULT_CHANNELS,
+            1024,
+        )?;
+        Ok(audio)
+    });
+    let (_audio, mut sound_effects) = match audio {
+        Ok(audio) => (Some(audio), SoundEffects::new()),
+        Err(e) => {
+            eprintln!("warning: cannot open audio: {}", e);
+            (None, SoundEffects::disabled())
+        }
+    };
+
+    let canvas = window.into_canvas().build().unwrap();
+    let texture_creator = canvas.texture_creator();
+    let mut graphics = Graphics::new(canvas, &texture_creator);
 
     let mut last_update_time: u32 = timer.ticks();
     let mut current_time: u32;
-    let mut dt: u32;
 
     let mut game = Game::new();
 
-    game.init_sprite(&texture_creator);
+    let record_path = get_argument("--record");
+    if record_path.is_some() {
+        game.start_recording();
+    }
+    if let Some(path) = get_argument("--replay") {
+        game.start_replay(InputReplay::from_file(Path::new(&path)).unwrap());
+    }
 
-    'running: loop {
-        input.begin_new_frame();
+    if let Err(e) = game.init_sprite(&mut graphics) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+    // 글꼴이 없으면 대화창은 내장 8x8 글꼴을 쓴다.
+    if let Err(e) = graphics.load_font(&ttf_context, Path::new(FONT_PATH)) {
+        eprintln!("warning: cannot load font {}: {}, using the built-in font", FONT_PATH, e);
+    }
 
+    'running: loop {
+        // 눌림/뗌 입력은 게임 틱에서 처리한 뒤 지운다.
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. } => break 'running,
@@ -43,37 +109,47 @@ fn main() {
                 Event::KeyUp { scancode, .. } => {
                     input.key_up_event(&scancode);
                 }
+                Event::ControllerDeviceAdded { which, .. } => {
+                    controllers.add(which);
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.remove(which);
+                    input.controller_removed(which);
+                }
+                Event::ControllerButtonDown { which, button, .. } => {
+                    input.button_down_event(which, button);
+                }
+                Event::ControllerButtonUp { which, button, .. } => {
+                    input.button_up_event(which, button);
+                }
+                Event::ControllerAxisMotion { which, axis, value, .. } => {
+                    input.axis_event(which, axis, value);
+                }
                 _ => {}
             }
         }
-        if input.was_key_pressed(Scancode::Escape) {
+        if input.was_pressed(Action::Quit) {
             break 'running;
         }
 
         current_time = timer.ticks();
-        dt = current_time - last_update_time;
-
-        canvas.clear();
-
-        game.process_key_event(&input);
-
-        let game_result = game.update(dt.min(MAX_FRAME_TIME));
-        game.render(&mut canvas);
+        game.advance(current_time - last_update_time, &mut input, &mut graphics);
+        last_update_time = current_time;
 
-        match game_result {
-            GameResult::None => {}
-            GameResult::GotoMap(map) => {
-                game.change_map(map, &texture_creator);
-            }
+        for sound in game.sounds.drain(..) {
+            sound_effects.play(sound);
         }
-        canvas.present();
 
-        last_update_time = current_time;
+        graphics.clear();
+        game.render(&mut graphics);
+        graphics.present();
+
+        // 한 프레임(1000 / FPS ms)에서 이번 프레임에 걸린 시간을 뺀 만큼 쉰다.
+        let frame_time = timer.ticks() - current_time;
+        ::std::thread::sleep(Duration::from_millis((1000 / FPS).saturating_sub(frame_time) as u64));
+    }
 
-        // some delays
-        // 아래의 시간은 1 nano sec (10억분의 1초) 기준이다.
-        // 즉 16666666.6667 ns = 16666.6666667 micro = 16.6667 ms
-        // 해당 정도의 시간에 대한 딜레이를 준 것
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    if let (Some(path), Some(recorder)) = (record_path, &game.recorder) {
+        recorder.save(Path::new(&path)).unwrap();
     }
 }
@@ -1,7 +1,17 @@
 pub const FPS: u32 = 60;
 pub const MAX_FRAME_TIME: u32 = 1000 / FPS;
 
+/// 한 프레임에 따라잡을 수 있는 최대 틱 수
+pub const MAX_TICKS_PER_FRAME: u32 = 5;
+
 pub const SCREEN_WIDTH: u32 = 640;
 pub const SCREEN_HEIGHT: u32 = 480;
 
 pub const SPRITE_SCALE: f32 = 2.0;
+
+/// 글자 출력에 쓰는 TTF 글꼴. 한글/일본어를 쓰려면 해당 글자가 있는 글꼴이어야 한다.
+/// 글꼴은 저장소에 들어있지 않으므로 직접 넣어야 한다. 없으면 내장 8x8 글꼴을 쓴다.
+pub const FONT_PATH: &str = "./resources/font.ttf";
+
+/// 키 설정 파일. 없으면 기본 키로 만든다.
+pub const CONTROLS_PATH: &str = "./controls.cfg";
@@ -1,16 +1,21 @@
+use std::io;
 use std::path::Path;
 
-use sdl2::{
-    rect::Rect,
-    render::{TextureCreator, WindowCanvas},
-    video::WindowContext,
-};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sdl2::{pixels::Color, rect::Rect};
 
-use super::{SCREEN_HEIGHT, SCREEN_WIDTH, SPRITE_SCALE};
+use super::FixedTimestep;
 use crate::{
-    graphics::{level::Level, Graphics, Hud},
-    input::Input,
-    player::Player,
+    constant::ASSET_DIR,
+    graphics::{
+        level::Level, AnimationSets, Camera, DamageNumber, Effect, Hud, Renderable, Renderer,
+        StageTable, FONT_SIZE,
+    },
+    input::{Action, Input, InputRecorder, InputReplay},
+    physics::collides_with,
+    player::{DropChance, EnemyRenderable, Pickup, Player, PICKUP_SIZE},
+    script::{ScriptResult, Transfer, TscInterpreter, TscScript},
+    sound::{SOUND_FOOTSTEP, SOUND_PLAYER_DEATH, SOUND_PLAYER_HURT},
     GameResult,
 };
 use std::collections::HashMap;
@@ -23,50 +28,298 @@ fn bool_to_sign(b: bool) -> i32 {
     }
 }
 
-#[derive(Default)]
-pub struct Game<'a> {
+/// 총알이 벽이나 적에 맞으면 사라지고 그 자리에 효과를 남긴다.
+fn update_bullets(
+    player: &mut Player,
+    level: &mut Level,
+    effects: &mut Vec<Effect>,
+    sounds: &mut Vec<u8>,
+    animations: &AnimationSets,
+) {
+    for bullet in player.bullets.iter_mut() {
+        let collision = bullet.get_collision();
+
+        if let Some(enemy) = level.collided_enemy_mut(&collision) {
+            enemy.take_damage(bullet.damage);
+            if !enemy.is_dead() {
+                sounds.push(enemy.get_hit_sound());
+            }
+            bullet.alive = false;
+        } else if !level.collided_blocks(&collision).is_empty() {
+            bullet.alive = false;
+        }
+
+        if !bullet.alive {
+            let (x, y) = bullet.get_center();
+            effects.push(Effect::impact(animations, x, y));
+        }
+    }
+
+    player.bullets.retain(|bullet| bullet.alive);
+}
+
+/// 아이템은 떨어지다 블록 위에 멈추고, 플레이어가 닿으면 주워진다.
+fn update_pickups(dt: u32, player: &mut Player, level: &Level, pickups: &mut Vec<Pickup>) {
+    for pickup in pickups.iter_mut() {
+        pickup.update(dt);
+        pickup.land(&level.collided_npc_blocks(&pickup.get_collision()));
+    }
+
+    pickups.retain(|pickup| {
+        if collides_with(&pickup.get_collision(), &player.collision) {
+            player.collect(pickup);
+            return false;
+        }
+        !pickup.is_expired()
+    });
+}
+
+/// 맵과 상관없이 쓰는 텍스처의 이름과 ASSET_DIR 아래 파일
+const TEXTURES: [(&str, &str); 8] = [
+    ("player", "mychar.png"),
+    ("textbox", "text_box.png"),
+    ("face", "Face.pbm"),
+    ("enemy", "npc_cemet.png"),
+    ("arms", "Arms.pbm"),
+    ("bullet", "Bullet.pbm"),
+    ("caret", "Caret.pbm"),
+    ("npc_sym", "npc_sym.png"),
+];
+
+/// 플레이어가 죽은 뒤 게임 오버 화면이 나올 때까지의 시간 (ms)
+pub const DEATH_DURATION: u32 = 1500;
+
+/// 게임 진행 상태
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Playing,
+    /// 플레이어가 죽고 게임 오버 화면까지 남은 시간 (ms)
+    Dying(u32),
+    /// 점프/발사로 다시 시작하고 Quit으로 끝낸다.
+    GameOver,
+    /// 다음 update에서 현재 맵을 처음부터 다시 읽는다.
+    Retry,
+}
+
+pub struct Game {
+    pub state: GameState,
+    /// 지금 맵 파일 이름. 다시 시작할 때 읽는다.
+    pub current_map: String,
     pub player: Option<Player>,
-    pub level: HashMap<String, Level<'a>>,
-    pub graphics: Graphics<'a>,
+    pub level: HashMap<String, Level>,
     pub hud: Option<Hud>,
+    /// 총알 충돌 같은 한 번 재생되고 사라지는 효과
+    pub effects: Vec<Effect>,
+    /// 플레이어가 맞았을 때 떠오르는 피해량
+    pub damage_numbers: Vec<DamageNumber>,
+    /// 바닥에 떨어진 경험치 등의 아이템
+    pub pickups: Vec<Pickup>,
+    /// 적이 죽을 때 아이템을 떨어뜨릴 확률
+    pub drop_chance: DropChance,
+    /// 이번 프레임에 낼 효과음 번호. 화면 쪽에서 재생하고 비운다.
+    pub sounds: Vec<u8>,
+    /// 스프라이트 애니메이션 정의
+    pub animations: AnimationSets,
+    /// 원본 스테이지 번호와 파일 이름
+    pub stages: StageTable,
+    pub camera: Camera,
+    pub timestep: FixedTimestep,
+    pub script: TscInterpreter,
+    transfer: Option<Transfer>,
+    /// 지나온 문이 가리키는 다음 맵의 입구 이름
+    entrance: Option<String>,
+    /// 게임 안에서 쓰는 모든 난수는 rng에서 뽑는다. 같은 seed와 입력이면 같은 결과가 나온다.
+    pub seed: u64,
+    pub rng: StdRng,
+    /// 틱마다 입력을 기록한다.
+    pub recorder: Option<InputRecorder>,
+    /// 기록된 입력을 틱마다 다시 넣는다. 키보드 입력은 무시된다.
+    pub replay: Option<InputReplay>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<'a> Game<'a> {
-    pub fn new() -> Game<'a> {
-        Game { player: None, level: HashMap::new(), graphics: Graphics::new(), hud: None }
+impl Game {
+    /// 임의의 seed로 시작한다.
+    pub fn new() -> Game {
+        Game::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Game {
+        Game {
+            state: GameState::Playing,
+            current_map: String::new(),
+            player: None,
+            level: HashMap::new(),
+            hud: None,
+            effects: vec![],
+            damage_numbers: vec![],
+            pickups: vec![],
+            drop_chance: DropChance::default(),
+            sounds: vec![],
+            animations: AnimationSets::default(),
+            stages: StageTable::default(),
+            camera: Camera::default(),
+            timestep: FixedTimestep::new(),
+            script: TscInterpreter::new(),
+            transfer: None,
+            entrance: None,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            recorder: None,
+            replay: None,
+        }
+    }
+
+    /// 지금부터 입력을 기록한다.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(InputRecorder::new(self.seed));
+    }
+
+    /// 기록된 seed로 난수를 다시 맞추고 입력을 재생한다. 맵을 읽기 전에 불러야 한다.
+    pub fn start_replay(&mut self, replay: InputReplay) {
+        self.seed = replay.seed;
+        self.rng = StdRng::seed_from_u64(replay.seed);
+        self.replay = Some(replay);
     }
 
-    pub fn init_sprite(&mut self, texture_creator: &'a TextureCreator<WindowContext>) {
-        self.change_map("stage.tmx".into(), texture_creator);
+    /// 애니메이션 정의와 스테이지 표를 읽고 첫 맵을 연다. 맵의 텍스처를 읽은 뒤 애니메이션 정의를 검사한다.
+    /// 파일을 읽을 수 없거나 검사에 실패하면 에러를 돌려준다.
+    pub fn init_sprite(&mut self, renderer: &mut dyn Renderer) -> io::Result<()> {
+        self.animations = AnimationSets::load()?;
+        self.stages = StageTable::load()?;
+        self.change_map("stage.tmx".into(), renderer)?;
+        self.animations.validate(renderer)
+    }
+
+    /// 지난 프레임 이후 흐른 시간(ms) 만큼 고정 틱을 진행하고, 진행한 틱 수를 반환한다.
+    pub fn advance(&mut self, elapsed: u32, input: &mut Input, renderer: &mut dyn Renderer) -> u32 {
+        self.timestep.accumulate(elapsed);
+
+        let mut ticks = 0;
+        while self.timestep.consume_tick() {
+            self.tick(input, renderer);
+            ticks += 1;
+        }
+        ticks
     }
 
-    pub fn render(&self, canvas: &mut WindowCanvas) {
+    /// 입력을 처리하고 한 틱(1 / FPS 초) 진행한다.
+    /// 눌림/뗌 입력은 처리한 뒤 지우므로, 틱이 없었던 프레임의 입력은 다음 틱으로 넘어간다.
+    pub fn tick(&mut self, input: &mut Input, renderer: &mut dyn Renderer) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.next(input);
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(input);
+        }
+
+        self.process_key_event(input);
+        input.begin_new_frame();
+
+        let dt = self.timestep.next_tick();
+        if let GameResult::GotoMap(map) = self.update(dt) {
+            // 맵을 읽지 못하면 지금 맵에 그대로 남는다. 다시 시작하려던 중이면 게임 오버로 돌아간다.
+            if let Err(e) = self.change_map(map.clone(), renderer) {
+                eprintln!("warning: cannot load map {}: {}", map, e);
+                self.transfer = None;
+                self.entrance = None;
+                if self.state == GameState::Retry {
+                    self.state = GameState::GameOver;
+                }
+            }
+        }
+    }
+
+    /// 직전 틱과 현재 틱 사이를 보간해서 그린다.
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        let alpha = self.timestep.alpha();
+        let camera = self.camera.interpolated(alpha);
+
         if let Some(map) = self.level.get("map") {
-            map.render(
-                canvas,
-                &Rect::new(
-                    0,
-                    0,
-                    (SCREEN_WIDTH as f32 / SPRITE_SCALE) as u32,
-                    (SCREEN_HEIGHT as f32 / SPRITE_SCALE) as u32,
-                ),
-            );
-            map.render_enemies(&self.graphics, canvas);
+            map.render(renderer, &camera.get_rect());
+            map.render_enemies(renderer, &camera, alpha);
         }
 
         if let Some(player) = &self.player {
-            self.graphics.render_sprite(canvas, player);
+            player.render(renderer, &camera, alpha);
+            for pickup in &self.pickups {
+                pickup.render(renderer, &camera, alpha);
+            }
+            for bullet in &player.bullets {
+                bullet.render(renderer, &camera, alpha);
+            }
+            for effect in &self.effects {
+                effect.render(renderer, &camera, alpha);
+            }
+            for number in &self.damage_numbers {
+                number.render(renderer, &camera, alpha);
+            }
             if let Some(hud) = &self.hud {
-                self.graphics.render_sprite(canvas, hud);
+                hud.render(renderer, &camera, alpha);
             }
         }
+
+        self.script.dialogue.render(renderer);
+
+        if self.state == GameState::GameOver {
+            self.render_game_over(renderer);
+        }
+    }
+
+    fn render_game_over(&self, renderer: &mut dyn Renderer) {
+        let (width, height) = (self.camera.width as i32, self.camera.height as i32);
+        renderer.fill_rect(Rect::new(0, 0, width as u32, height as u32), Color::BLACK);
+
+        for (i, text) in ["GAME OVER", "Jump: Retry", "Quit: Exit"].iter().enumerate() {
+            let text_width = renderer.measure_text(text, FONT_SIZE) as i32;
+            let y = height / 2 - FONT_SIZE as i32 + i as i32 * FONT_SIZE as i32 * 2;
+            renderer.draw_text(text, (width - text_width) / 2, y, FONT_SIZE, Color::WHITE);
+        }
     }
 
     pub fn update(&mut self, dt: u32) -> GameResult {
+        match self.state {
+            GameState::Playing => {}
+            GameState::Dying(time) => {
+                self.update_effects(dt);
+                self.state =
+                    if time > dt { GameState::Dying(time - dt) } else { GameState::GameOver };
+                return GameResult::None;
+            }
+            GameState::GameOver => return GameResult::None,
+            // 맵을 다시 읽은 뒤에 처음 상태의 플레이어로 바꾼다.
+            GameState::Retry => return GameResult::GotoMap(self.current_map.clone()),
+        }
+
+        if let ScriptResult::Transfer(transfer) = self.script.update(dt) {
+            if let Some(stage) = self.stages.get(transfer.map) {
+                self.transfer = Some(transfer);
+                return GameResult::GotoMap(stage.file.clone());
+            }
+            // 스테이지 표에 없는 맵으로는 이동할 수 없으므로 이벤트만 끝낸다.
+            eprintln!("warning: unknown stage {}", transfer.map);
+            self.script.end();
+        }
+
+        // <PRI 중에는 스크립트만 진행된다.
+        if self.script.world_frozen {
+            return GameResult::None;
+        }
+
+        self.update_effects(dt);
+
         if let Some(player) = self.player.as_mut() {
             player.update(dt);
-            if let Some(hud) = self.hud.as_mut() {
-                hud.update(player);
+
+            for event in player.take_animation_events() {
+                if event == "footstep" && player.is_grounded() {
+                    self.sounds.push(SOUND_FOOTSTEP);
+                }
             }
 
             if let Some(level) = self.level.get_mut("map") {
@@ -82,91 +335,264 @@ impl<'a> Game<'a> {
                     player.handle_slope_collision(&collided_slopes);
                 }
 
+                // event
+                if !self.script.is_running() {
+                    if let Some(event) =
+                        level.triggered_event(&player.collision, player.is_interacting())
+                    {
+                        player.take_interaction();
+                        self.script.start_event(event);
+                    }
+                }
+
                 // door
+                // 문에 닿지 않았어도 눌린 아래 키는 이번 틱에 지운다.
                 let collided_doors = level.collided_doors(&player.collision);
-                if !collided_doors.is_empty() {
-                    let level = player.handle_door_collision(&collided_doors);
-
-                    if !level.is_empty() {
-                        return GameResult::GotoMap(level);
-                    }
+                if let Some(door) = player.handle_door_collision(&collided_doors) {
+                    self.entrance = door.entrance;
+                    return GameResult::GotoMap(door.destination);
                 }
 
                 // collision enemies
                 let enemies = level.collided_enemies(&player.collision);
-                if !enemies.is_empty() {
-                    player.take_damage(1);
+                if let Some(enemy) = enemies.iter().max_by_key(|enemy| enemy.get_damage()) {
+                    let damage = enemy.get_damage();
+                    if player.take_damage(damage, enemy.get_collision().center().x()) {
+                        let (x, y) = player.get_position();
+                        self.damage_numbers.push(DamageNumber::new(damage, x + 8, y));
+                        self.sounds.push(if player.is_dead() {
+                            SOUND_PLAYER_DEATH
+                        } else {
+                            SOUND_PLAYER_HURT
+                        });
+                    }
                 }
 
                 level.update(dt, player);
+                update_bullets(
+                    player,
+                    level,
+                    &mut self.effects,
+                    &mut self.sounds,
+                    &self.animations,
+                );
+                update_pickups(dt, player, level, &mut self.pickups);
+
+                self.camera.follow(
+                    dt,
+                    player.collision,
+                    player.get_look_direction(),
+                    level.get_pixel_size(),
+                );
+            }
+
+            if let Some(hud) = self.hud.as_mut() {
+                hud.update(dt, player);
+            }
+
+            if player.is_dead() {
+                let center = player.collision.center();
+                for _ in 0..8 {
+                    let x = center.x() + self.rng.gen_range(-8..=8);
+                    let y = center.y() + self.rng.gen_range(-8..=8);
+                    self.effects.push(Effect::smoke(&self.animations, x, y));
+                }
+                self.state = GameState::Dying(DEATH_DURATION);
+                return GameResult::None;
+            }
+        }
+
+        if let Some(level) = self.level.get_mut("map") {
+            for enemy in level.remove_dead_enemies() {
+                self.kill_enemy(enemy.as_ref());
             }
         }
 
         GameResult::None
     }
 
-    pub fn change_map(
-        &mut self,
-        map_name: String,
-        texture_creator: &'a TextureCreator<WindowContext>,
-    ) {
-        let map = Level::new(texture_creator, map_name);
-        let player_pos = (map.start_pos.left() as i32, map.start_pos.top() as i32);
+    fn update_effects(&mut self, dt: u32) {
+        self.effects.iter_mut().for_each(|effect| effect.update(dt));
+        self.effects.retain(|effect| !effect.is_finished());
 
-        self.level.insert("map".into(), map);
+        self.damage_numbers.iter_mut().for_each(|number| number.update(dt));
+        self.damage_numbers.retain(|number| !number.is_finished());
+    }
+
+    /// 죽은 적 자리에 연기를 피우고 아이템을 떨어뜨린다.
+    fn kill_enemy(&mut self, enemy: &dyn EnemyRenderable) {
+        let collision = enemy.get_collision();
+        let (x, y) = (collision.center().x(), collision.center().y());
+
+        self.sounds.push(enemy.get_death_sound());
+        for _ in 0..enemy.get_smoke_size().max(1) * 3 {
+            let offset_x = self.rng.gen_range(-8..=8);
+            let offset_y = self.rng.gen_range(-8..=8);
+            self.effects.push(Effect::smoke(&self.animations, x + offset_x, y + offset_y));
+        }
+
+        let roll = self.rng.gen_range(0..100);
+        if let Some(kind) = self.drop_chance.pick(roll, enemy.get_experience()) {
+            let size = PICKUP_SIZE as i32;
+            self.pickups.push(Pickup::new(x - size / 2, y - size / 2, kind, &self.animations));
+        }
+    }
+
+    /// .tmx는 Tiled 맵으로, 그 밖의 이름은 스테이지 표에 있는 원본 스테이지(.pxm)로 읽는다.
+    fn load_level(&self, map_name: &str, renderer: &mut dyn Renderer) -> io::Result<Level> {
+        if Path::new(map_name).extension().is_some_and(|ext| ext == "tmx") {
+            return Level::new(renderer, map_name.into(), &self.animations);
+        }
+
+        let stage = self.stages.get_by_file(map_name).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("unknown stage: {}", map_name))
+        })?;
+        Level::from_pxm(renderer, &stage.file, &stage.tileset, &self.animations)
+    }
+
+    /// 텍스처나 맵을 읽을 수 없으면 지금 맵을 그대로 두고 에러를 돌려준다.
+    pub fn change_map(&mut self, map_name: String, renderer: &mut dyn Renderer) -> io::Result<()> {
+        for (name, file) in TEXTURES {
+            renderer
+                .load_texture(name, Path::new(&(ASSET_DIR.to_owned() + file)))
+                .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+        }
+        let map = self.load_level(&map_name, renderer)?;
+        let (stage, entry_event) = (map.stage.clone(), map.entry_event);
+        let mut player_pos = (map.start_pos.left() as i32, map.start_pos.top() as i32);
+        let mut facing = None;
 
-        let player = Player::new(player_pos.0, player_pos.1);
+        // 문으로 들어온 경우에는 문이 가리키는 입구에서 시작한다.
+        if let Some(name) = self.entrance.take() {
+            match map.get_entrance(&name) {
+                Some(entrance) => {
+                    player_pos = (entrance.position.left() as i32, entrance.position.top() as i32);
+                    facing = entrance.facing;
+                }
+                None => eprintln!("warning: {} has no entrance {}", map_name, name),
+            }
+        }
+
+        // <TRA로 이동한 경우에는 지정된 타일 위치에서 시작한다.
+        let transfer = self.transfer.take();
+        if let Some(transfer) = transfer {
+            player_pos = (
+                (transfer.x as u32 * map.tile_width) as i32,
+                (transfer.y as u32 * map.tile_height) as i32,
+            );
+        }
+
+        // 맵을 옮겨도 플레이어의 체력, 무기, 경험치는 그대로이다.
+        // 다시 시작할 때는 처음 상태의 플레이어와 HUD로 바꾼다.
+        let retry = self.state == GameState::Retry;
+        if retry {
+            self.hud = None;
+        }
+        let mut player = self
+            .player
+            .take()
+            .filter(|_| !retry)
+            .unwrap_or_else(|| Player::new(player_pos.0, player_pos.1, &self.animations));
+        player.enter_map(player_pos.0, player_pos.1, facing);
+        self.camera.snap(player.collision, map.get_pixel_size());
+
+        self.level.insert("map".into(), map);
         self.player = Some(player);
+        self.effects.clear();
+        self.damage_numbers.clear();
+        self.pickups.clear();
+        self.state = GameState::Playing;
+        self.current_map = map_name.clone();
 
-        let hud = Hud::new();
-        self.hud = Some(hud);
+        // 공통 이벤트(Head.tsc)와 맵의 스테이지 스크립트(Stage/<stage>.tsc)를 읽는다.
+        let mut script = TscScript::from_file(Path::new(&(ASSET_DIR.to_owned() + "Head.tsc")))
+            .unwrap_or_default();
+        if let Some(stage) = stage {
+            let path = format!("{}Stage/{}.tsc", ASSET_DIR, stage);
+            match TscScript::from_file(Path::new(&path)) {
+                Ok(stage_script) => script.events.extend(stage_script.events),
+                Err(e) => eprintln!("warning: cannot load script {}: {}", path, e),
+            }
+        }
+        self.script.load(script);
 
-        self.graphics.load_image(
-            texture_creator,
-            "player".into(),
-            Path::new("resources/mychar.png"),
-        );
-        self.graphics.load_image(
-            texture_creator,
-            "textbox".into(),
-            Path::new("resources/text_box.png"),
-        );
+        if let Some(event) = transfer.map(|transfer| transfer.event).or(entry_event) {
+            self.script.start_event(event);
+        }
 
-        self.graphics.load_image(
-            texture_creator,
-            "enemy".into(),
-            Path::new("resources/npc_cemet.png"),
-        );
+        if self.hud.is_none() {
+            self.hud = Some(Hud::new());
+        }
+        Ok(())
     }
 
     pub fn process_key_event(&mut self, input: &Input) {
+        match self.state {
+            GameState::Playing => {}
+            GameState::GameOver => {
+                if input.was_pressed(Action::Jump) || input.was_pressed(Action::Shoot) {
+                    self.state = GameState::Retry;
+                }
+                return;
+            }
+            _ => return,
+        }
+
+        if self.script.is_running()
+            && (input.was_pressed(Action::Jump) || input.was_pressed(Action::Shoot))
+        {
+            self.script.confirm();
+        }
+
+        if input.was_pressed(Action::MoveLeft) {
+            self.script.select(true);
+        } else if input.was_pressed(Action::MoveRight) {
+            self.script.select(false);
+        }
+
         let player = self.player.as_mut().unwrap();
 
-        if input.is_key_held(sdl2::keyboard::Scancode::Right) {
+        if self.script.input_locked {
+            player.stop();
+            return;
+        }
+
+        if input.is_held(Action::MoveRight) {
             player.move_right();
-        } else if input.is_key_held(sdl2::keyboard::Scancode::Left) {
+        } else if input.is_held(Action::MoveLeft) {
             player.move_left();
-        } else if !input.is_key_held(sdl2::keyboard::Scancode::Right)
-            && !input.is_key_held(sdl2::keyboard::Scancode::Left)
-        {
+        } else {
             player.stop();
         }
 
-        if input.is_key_held(sdl2::keyboard::Scancode::Up) {
+        if input.is_held(Action::LookUp) {
             player.look_up();
-        } else if input.is_key_held(sdl2::keyboard::Scancode::Down) {
+        } else if input.is_held(Action::LookDown) {
             player.look_down();
         }
 
-        if input.was_key_release(sdl2::keyboard::Scancode::Up) {
+        if input.was_released(Action::LookUp) {
             player.stop_looking_up();
         }
-        if input.was_key_release(sdl2::keyboard::Scancode::Down) {
+        if input.was_released(Action::LookDown) {
             player.stop_looking_down();
         }
+        if input.was_pressed(Action::LookDown) {
+            player.interact();
+        }
 
-        if input.is_key_held(sdl2::keyboard::Scancode::Z) {
+        if input.is_held(Action::Jump) {
             player.jump();
         }
+
+        if input.was_pressed(Action::Shoot) {
+            player.shoot();
+        }
+
+        if input.was_pressed(Action::NextWeapon) {
+            player.next_weapon();
+        } else if input.was_pressed(Action::PreviousWeapon) {
+            player.previous_weapon();
+        }
     }
 }
@@ -1,5 +1,7 @@
 pub mod constant;
 pub mod game;
+pub mod timestep;
 
 pub use constant::*;
 pub use game::*;
+pub use timestep::*;
@@ -0,0 +1,141 @@
+use super::{FPS, MAX_TICKS_PER_FRAME};
+
+/// 누산기는 1/FPS ms 단위로 시간을 센다. 이 단위로 한 틱은 정확히 1000이다.
+const TICK_UNITS: u32 = 1000;
+
+/// n번째 틱에 update로 넘길 시간 (ms)
+/// 한 틱은 1000 / FPS ms라 나누어 떨어지지 않으므로, n틱까지의 합이 n * 1000 / FPS가 되도록
+/// 16, 17 ms를 번갈아 돌려준다.
+fn tick_duration(tick: u64) -> u32 {
+    let elapsed = |tick: u64| tick * 1000 / FPS as u64;
+    (elapsed(tick + 1) - elapsed(tick)) as u32
+}
+
+/// 고정된 틱 길이로 게임을 진행하기 위한 누산기
+/// 프레임마다 흐른 시간을 쌓고, 1 / FPS 초씩 꺼내 쓴다.
+/// 남은 시간은 렌더링 때 직전 틱과 현재 틱 사이를 보간하는 데 쓴다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FixedTimestep {
+    /// 쌓인 시간 (1/FPS ms)
+    accumulator: u32,
+    /// 지금까지 진행한 틱 수
+    pub ticks: u64,
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 지난 프레임 이후 흐른 시간(ms)을 쌓는다.
+    /// 창을 옮기는 등으로 오래 멈췄던 경우에는 MAX_TICKS_PER_FRAME 틱 만큼만 따라잡는다.
+    pub fn accumulate(&mut self, elapsed: u32) {
+        self.accumulator = (self.accumulator + elapsed * FPS).min(TICK_UNITS * MAX_TICKS_PER_FRAME);
+    }
+
+    /// 한 틱만큼 시간이 쌓였다면 꺼내고 true를 반환한다.
+    pub fn consume_tick(&mut self) -> bool {
+        if self.accumulator < TICK_UNITS {
+            return false;
+        }
+
+        self.accumulator -= TICK_UNITS;
+        true
+    }
+
+    /// 틱 하나를 진행하고 그 틱에 update로 넘길 시간(ms)을 반환한다.
+    pub fn next_tick(&mut self) -> u32 {
+        let dt = tick_duration(self.ticks);
+        self.ticks += 1;
+        dt
+    }
+
+    /// 다음 틱까지 진행된 비율 (0 ~ 1)
+    pub fn alpha(&self) -> f32 {
+        self.accumulator as f32 / TICK_UNITS as f32
+    }
+}
+
+/// 직전 틱의 위치와 현재 위치 사이를 alpha 비율로 보간한다.
+pub fn interpolate(previous: (i32, i32), current: (i32, i32), alpha: f32) -> (i32, i32) {
+    (
+        previous.0 + ((current.0 - previous.0) as f32 * alpha).round() as i32,
+        previous.1 + ((current.1 - previous.1) as f32 * alpha).round() as i32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consume_all(timestep: &mut FixedTimestep) -> u32 {
+        let mut ticks = 0;
+        while timestep.consume_tick() {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn one_second_is_fps_ticks() {
+        let mut timestep = FixedTimestep::new();
+        let mut ticks = 0;
+        for _ in 0..1000 {
+            timestep.accumulate(1);
+            ticks += consume_all(&mut timestep);
+        }
+        assert_eq!(ticks, FPS);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn accumulates_partial_ticks() {
+        let mut timestep = FixedTimestep::new();
+
+        timestep.accumulate(16);
+        assert_eq!(consume_all(&mut timestep), 0);
+
+        timestep.accumulate(1);
+        assert_eq!(consume_all(&mut timestep), 1);
+
+        timestep.accumulate(34);
+        assert_eq!(consume_all(&mut timestep), 2);
+    }
+
+    #[test]
+    fn clamps_long_frames() {
+        let mut timestep = FixedTimestep::new();
+
+        timestep.accumulate(1000);
+        assert_eq!(consume_all(&mut timestep), MAX_TICKS_PER_FRAME);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn alpha_is_remaining_fraction() {
+        let mut timestep = FixedTimestep::new();
+        assert_eq!(timestep.alpha(), 0.0);
+
+        // 25ms는 1.5틱이다.
+        timestep.accumulate(25);
+        assert_eq!(consume_all(&mut timestep), 1);
+        assert_eq!(timestep.alpha(), 0.5);
+    }
+
+    #[test]
+    fn tick_durations_add_up_to_whole_seconds() {
+        let mut timestep = FixedTimestep::new();
+        let durations: Vec<u32> = (0..FPS * 3).map(|_| timestep.next_tick()).collect();
+
+        assert_eq!(durations.iter().sum::<u32>(), 3000);
+        assert!(durations.iter().all(|dt| matches!(dt, 16 | 17)));
+        assert_eq!(timestep.ticks, FPS as u64 * 3);
+    }
+
+    #[test]
+    fn interpolates_between_ticks() {
+        assert_eq!(interpolate((0, 0), (10, -10), 0.0), (0, 0));
+        assert_eq!(interpolate((0, 0), (10, -10), 0.5), (5, -5));
+        assert_eq!(interpolate((0, 0), (10, -10), 1.0), (10, -10));
+    }
+}
@@ -1,6 +1,6 @@
-use crate::game::SPRITE_SCALE;
 use crate::graphics::animation::Animation;
-use sdl2::{rect::Rect, render::Texture, render::WindowCanvas};
+use crate::graphics::{AnimationSet, Renderer, Transform};
+use sdl2::rect::Rect;
 use std::collections::HashMap;
 
 #[derive(Clone, Default)]
@@ -8,11 +8,34 @@ pub struct AnimateSprite {
     pub name: String,
     pub sprites: HashMap<String, Animation>,
     pub current_animation: String,
+    /// 한 번만 재생하는 현재 애니메이션이 끝나면 이어서 재생할 애니메이션
+    pub next_animation: Option<String>,
 }
 
 impl AnimateSprite {
     pub fn new(name: String) -> Self {
-        Self { name, sprites: HashMap::new(), current_animation: "".into() }
+        Self { name, sprites: HashMap::new(), current_animation: "".into(), next_animation: None }
+    }
+
+    /// 정의 파일에서 읽은 세트의 애니메이션을 모두 등록한다. 처음에는 첫 번째 애니메이션을 재생한다.
+    pub fn from_set(set: &AnimationSet) -> Self {
+        let mut sprite = AnimateSprite::new(set.sheet.clone());
+        for definition in &set.animations {
+            let mut animation = Animation::from_frames(
+                definition.get_frames(),
+                definition.get_durations(),
+                definition.play_once,
+            )
+            .with_mode(definition.mode);
+            for (frame, event) in &definition.events {
+                animation.add_event(*frame, event.clone());
+            }
+            sprite.sprites.insert(definition.name.clone(), animation);
+        }
+        if let Some(first) = set.animations.first() {
+            sprite.current_animation = first.name.clone();
+        }
+        sprite
     }
 
     pub fn add_animation(
@@ -28,37 +51,77 @@ impl AnimateSprite {
         self.sprites.insert(name, animation);
     }
 
+    /// 다른 애니메이션으로 바꾸면 그 애니메이션을 처음부터 재생한다.
+    /// 이어서 재생하려던 애니메이션은 취소된다. 없는 애니메이션이면 바꾸지 않는다.
     pub fn set_animation(&mut self, name: String) {
         if self.current_animation.ne(&name) {
-            self.current_animation = name;
+            if let Some(animation) = self.sprites.get_mut(&name) {
+                animation.reset();
+                self.current_animation = name;
+                self.next_animation = None;
+            }
         }
     }
 
+    /// 현재 애니메이션이 끝나면 name 애니메이션으로 넘어간다.
+    pub fn set_next_animation(&mut self, name: String) {
+        self.next_animation = Some(name);
+    }
+
     pub fn update(&mut self, dt: u32) {
-        let current_animation = self.sprites.get_mut(&self.current_animation).unwrap();
-        current_animation.update(dt);
+        let finished = match self.sprites.get_mut(&self.current_animation) {
+            Some(current_animation) => {
+                current_animation.update(dt);
+                current_animation.is_finished()
+            }
+            None => return,
+        };
+
+        if finished {
+            if let Some(next) = self.next_animation.take() {
+                self.set_animation(next);
+            }
+        }
+    }
+
+    /// 한 번만 재생하는 애니메이션이 끝났는가
+    pub fn is_finished(&self) -> bool {
+        self.sprites.get(&self.current_animation).is_none_or(Animation::is_finished)
+    }
+
+    /// 현재 애니메이션에서 지난번 이후로 일어난 프레임 이벤트들
+    pub fn take_events(&mut self) -> Vec<String> {
+        self.sprites
+            .get_mut(&self.current_animation)
+            .map(Animation::take_events)
+            .unwrap_or_default()
     }
 
     pub fn get_name(&self) -> String {
         self.name.clone()
     }
 
-    pub fn render(&self, x: i32, y: i32, canvas: &mut WindowCanvas, texture: &Texture) {
-        let current_animation = self.sprites.get(&(self.current_animation)).unwrap();
+    pub fn render(&self, x: i32, y: i32, renderer: &mut dyn Renderer) {
+        self.render_transformed(x, y, renderer, Transform::default());
+    }
 
-        if current_animation.visible {
-            let current_sprite = current_animation.get_current_frame();
+    /// 현재 프레임을 뒤집거나 돌려서 그린다.
+    pub fn render_transformed(
+        &self,
+        x: i32,
+        y: i32,
+        renderer: &mut dyn Renderer,
+        transform: Transform,
+    ) {
+        // 애니메이션이 없는 스프라이트는 그리지 않는다.
+        let current_animation = match self.sprites.get(&(self.current_animation)) {
+            Some(current_animation) => current_animation,
+            None => return,
+        };
+        let current_sprite = current_animation.get_current_frame();
 
-            let dest = Rect::new(
-                (x as f32 * SPRITE_SCALE) as i32,
-                (y as f32 * SPRITE_SCALE) as i32,
-                (current_sprite.width() as f32 * SPRITE_SCALE) as u32,
-                (current_sprite.height() as f32 * SPRITE_SCALE) as u32,
-            );
+        let dest = Rect::new(x, y, current_sprite.width(), current_sprite.height());
 
-            canvas
-                .copy_ex(texture, Some(current_sprite), Some(dest), 0.0, None, false, false)
-                .unwrap();
-        }
+        renderer.copy(&self.name, current_sprite, dest, transform);
     }
 }
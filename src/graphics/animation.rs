@@ -1,13 +1,34 @@
 use sdl2::rect::Rect;
 
+/// 프레임을 넘기는 순서
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayMode {
+    /// 첫 프레임부터 마지막 프레임으로
+    #[default]
+    Forward,
+    /// 마지막 프레임부터 첫 프레임으로
+    Reverse,
+    /// 마지막 프레임까지 갔다가 첫 프레임으로 되돌아온다.
+    PingPong,
+}
+
 #[derive(Default, Clone)]
 pub struct Animation {
     frames: Vec<Rect>,
     frame_index: usize,
+    /// 현재 프레임에서 흐른 시간 (ms). 프레임을 넘기고 남은 시간은 다음 프레임으로 넘어간다.
     time_elapsed: u32,
-    frame_duration: u32,
+    /// 프레임별 시간 (ms)
+    frame_durations: Vec<u32>,
     play_once: bool,
-    pub visible: bool,
+    mode: PlayMode,
+    /// PingPong에서 첫 프레임 쪽으로 돌아가는 중인가
+    backwards: bool,
+    finished: bool,
+    /// 프레임 번호와 그 프레임이 시작될 때 일어나는 이벤트 이름
+    events: Vec<(usize, String)>,
+    /// 아직 가져가지 않은 이벤트
+    fired_events: Vec<String>,
 }
 
 impl Animation {
@@ -32,31 +53,192 @@ impl Animation {
             }
         }
 
-        Self {
-            frames,
-            frame_index: 0,
-            time_elapsed: 0,
-            frame_duration: frame_length,
-            play_once,
-            visible: true,
-        }
+        let frame_durations = vec![frame_length; frames.len()];
+        Animation::from_frames(frames, frame_durations, play_once)
+    }
+
+    /// frame_durations는 frames와 같은 길이여야 한다.
+    pub fn from_frames(frames: Vec<Rect>, frame_durations: Vec<u32>, play_once: bool) -> Self {
+        Self { frames, frame_durations, play_once, ..Default::default() }
+    }
+
+    /// 재생 순서를 바꾸고 처음부터 다시 재생한다.
+    pub fn with_mode(mut self, mode: PlayMode) -> Self {
+        self.mode = mode;
+        self.reset();
+        self
+    }
+
+    /// frame이 시작될 때마다 name 이벤트가 일어난다.
+    pub fn add_event(&mut self, frame: usize, name: String) {
+        self.events.push((frame, name));
+    }
+
+    /// 처음부터 다시 재생한다. 첫 프레임의 이벤트도 다시 일어난다.
+    pub fn reset(&mut self) {
+        self.time_elapsed = 0;
+        self.backwards = false;
+        self.finished = false;
+        self.fired_events.clear();
+
+        let first = match self.mode {
+            PlayMode::Reverse => self.frames.len().saturating_sub(1),
+            _ => 0,
+        };
+        self.enter_frame(first);
     }
 
     pub fn get_current_frame(&self) -> Rect {
         self.frames[self.frame_index]
     }
 
+    pub fn get_frame_index(&self) -> usize {
+        self.frame_index
+    }
+
+    /// 한 번만 재생하는 애니메이션이 마지막 프레임까지 재생되었는가
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 지난번에 가져간 뒤로 일어난 이벤트들을 가져간다.
+    pub fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.fired_events)
+    }
+
     pub fn update(&mut self, dt: u32) {
+        if self.finished || self.frames.is_empty() {
+            return;
+        }
+
         self.time_elapsed += dt;
-        if self.time_elapsed > self.frame_duration {
-            self.time_elapsed = 0;
-            self.frame_index += 1;
+        // 시간이 0인 프레임에서 멈추지 않도록 적어도 1ms는 머문다.
+        while !self.finished && self.time_elapsed >= self.frame_durations[self.frame_index].max(1) {
+            self.time_elapsed -= self.frame_durations[self.frame_index].max(1);
+            match self.next_frame() {
+                Some(index) => self.enter_frame(index),
+                None => {
+                    self.finished = true;
+                    self.time_elapsed = 0;
+                }
+            }
         }
+    }
 
-        if self.frame_index >= self.frames.len() {
-            self.frame_index = 0;
+    /// 다음에 보일 프레임. 한 번만 재생하는 애니메이션이 끝났으면 None
+    fn next_frame(&mut self) -> Option<usize> {
+        let last = self.frames.len() - 1;
+        let index = self.frame_index;
 
-            self.visible = !self.play_once;
+        match self.mode {
+            PlayMode::Forward if index < last => Some(index + 1),
+            PlayMode::Forward => (!self.play_once).then_some(0),
+            PlayMode::Reverse if index > 0 => Some(index - 1),
+            PlayMode::Reverse => (!self.play_once).then_some(last),
+            PlayMode::PingPong if !self.backwards && index < last => Some(index + 1),
+            PlayMode::PingPong if !self.backwards => {
+                self.backwards = true;
+                if last > 0 {
+                    Some(last - 1)
+                } else {
+                    (!self.play_once).then_some(0)
+                }
+            }
+            PlayMode::PingPong if index > 0 => Some(index - 1),
+            PlayMode::PingPong => {
+                if self.play_once {
+                    return None;
+                }
+                self.backwards = false;
+                Some(1.min(last))
+            }
         }
     }
+
+    fn enter_frame(&mut self, index: usize) {
+        self.frame_index = index;
+        for (_, name) in self.events.iter().filter(|(frame, _)| *frame == index) {
+            self.fired_events.push(name.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16x16 프레임 count개, 프레임마다 100ms
+    fn animation(count: usize, play_once: bool, mode: PlayMode) -> Animation {
+        Animation::new(Rect::new(0, 0, 16, 16), 100, play_once, count, 1).with_mode(mode)
+    }
+
+    /// 100ms씩 steps번 진행하며 거친 프레임 번호들
+    fn play(animation: &mut Animation, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animation.update(100);
+                animation.get_frame_index()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn forward_loops_and_once_stops() {
+        let mut looping = animation(3, false, PlayMode::Forward);
+        assert_eq!(play(&mut looping, 4), [1, 2, 0, 1]);
+
+        let mut once = animation(3, true, PlayMode::Forward);
+        assert_eq!(play(&mut once, 4), [1, 2, 2, 2]);
+        assert!(once.is_finished());
+    }
+
+    #[test]
+    fn reverse_starts_from_last_frame() {
+        let mut reverse = animation(3, false, PlayMode::Reverse);
+        assert_eq!(reverse.get_frame_index(), 2);
+        assert_eq!(reverse.get_current_frame(), Rect::new(32, 0, 16, 16));
+        assert_eq!(play(&mut reverse, 4), [1, 0, 2, 1]);
+    }
+
+    #[test]
+    fn ping_pong_does_not_repeat_end_frames() {
+        let mut ping_pong = animation(3, false, PlayMode::PingPong);
+        assert_eq!(play(&mut ping_pong, 6), [1, 2, 1, 0, 1, 2]);
+
+        let mut once = animation(3, true, PlayMode::PingPong);
+        assert_eq!(play(&mut once, 5), [1, 2, 1, 0, 0]);
+        assert!(once.is_finished());
+    }
+
+    #[test]
+    fn carries_remaining_time_to_next_frame() {
+        let mut frames = Animation::from_frames(
+            vec![Rect::new(0, 0, 16, 16), Rect::new(16, 0, 16, 16), Rect::new(32, 0, 16, 16)],
+            vec![100, 50, 100],
+            false,
+        );
+
+        frames.update(120);
+        assert_eq!(frames.get_frame_index(), 1);
+        // 남은 20ms에 30ms를 더하면 두 번째 프레임(50ms)이 끝난다.
+        frames.update(30);
+        assert_eq!(frames.get_frame_index(), 2);
+        // 한 번에 여러 프레임을 넘길 수 있다.
+        frames.update(200);
+        assert_eq!(frames.get_frame_index(), 1);
+    }
+
+    #[test]
+    fn fires_events_when_entering_frames() {
+        let mut walking = animation(3, false, PlayMode::Forward);
+        walking.add_event(0, "step".into());
+        walking.add_event(2, "step".into());
+        walking.reset();
+
+        assert_eq!(walking.take_events(), ["step"]);
+        play(&mut walking, 1);
+        assert!(walking.take_events().is_empty());
+        play(&mut walking, 2);
+        assert_eq!(walking.take_events(), ["step", "step"]);
+    }
 }
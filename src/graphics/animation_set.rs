@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use sdl2::rect::Rect;
+
+use crate::constant::ASSET_DIR;
+
+use super::{AnimateSprite, PlayMode, Renderer};
+
+/// 애니메이션 정의 파일. ASSET_DIR 아래에 있다.
+pub const ANIMATIONS_FILE: &str = "animations.cfg";
+
+/// 게임 코드가 이름으로 찾는 세트. 정의 파일에 없으면 validate가 알려준다.
+pub const REQUIRED_ANIMATION_SETS: [&str; 7] =
+    ["player", "bat", "impact", "smoke", "experience", "heart", "missile"];
+
+/// 애니메이션 하나의 정의
+/// rect 크기의 프레임이 rect 위치부터 가로 horizontal 칸, 세로 vertical 칸 늘어서 있다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationDefinition {
+    pub name: String,
+    pub rect: Rect,
+    pub horizontal: usize,
+    pub vertical: usize,
+    /// 프레임별 시간 (ms). 값이 하나면 모든 프레임에 쓴다.
+    pub durations: Vec<u32>,
+    pub play_once: bool,
+    pub mode: PlayMode,
+    /// 프레임 번호와 그 프레임이 시작될 때 일어나는 이벤트 이름
+    pub events: Vec<(usize, String)>,
+}
+
+impl AnimationDefinition {
+    /// `x, y, 폭, 높이, 가로 칸 수, 세로 칸 수, 프레임 시간, 재생 방법[, 이벤트@프레임 ...]`
+    /// 프레임 시간이 프레임마다 다르면 `100/50/100` 처럼 `/`로 구분한다.
+    /// 재생 방법은 `loop` 또는 `once`이고, 앞에 `reverse`나 `pingpong`을 붙일 수 있다. (`pingpong once`)
+    /// 이벤트는 `footstep@1` 처럼 0부터 센 프레임 번호를 붙인다.
+    fn parse(name: &str, value: &str) -> Result<AnimationDefinition> {
+        let invalid = || invalid_data(format!("invalid animation: {} = {}", name, value));
+
+        let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+        if fields.len() < 8 {
+            return Err(invalid());
+        }
+
+        let number = |i: usize| fields[i].parse::<u32>().map_err(|_| invalid());
+        let (x, y, width, height) = (number(0)?, number(1)?, number(2)?, number(3)?);
+        let (horizontal, vertical) = (number(4)? as usize, number(5)? as usize);
+        let durations = fields[6]
+            .split('/')
+            .map(|duration| duration.trim().parse::<u32>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u32>>>()?;
+        let (mode, play_once) = match fields[7].split_whitespace().collect::<Vec<_>>()[..] {
+            ["loop"] => (PlayMode::Forward, false),
+            ["once"] => (PlayMode::Forward, true),
+            ["reverse"] | ["reverse", "loop"] => (PlayMode::Reverse, false),
+            ["reverse", "once"] => (PlayMode::Reverse, true),
+            ["pingpong"] | ["pingpong", "loop"] => (PlayMode::PingPong, false),
+            ["pingpong", "once"] => (PlayMode::PingPong, true),
+            _ => return Err(invalid()),
+        };
+        let events = fields[8..]
+            .iter()
+            .map(|event| {
+                let (event, frame) = event.split_once('@').ok_or_else(invalid)?;
+                let frame = frame.trim().parse::<usize>().map_err(|_| invalid())?;
+                Ok((frame, event.trim().to_string()))
+            })
+            .collect::<Result<Vec<(usize, String)>>>()?;
+
+        if width == 0 || height == 0 || horizontal == 0 || vertical == 0 {
+            return Err(invalid());
+        }
+        if durations.len() != 1 && durations.len() != horizontal * vertical {
+            return Err(invalid_data(format!(
+                "animation {} has {} frames but {} durations",
+                name,
+                horizontal * vertical,
+                durations.len()
+            )));
+        }
+        if let Some((frame, event)) =
+            events.iter().find(|(frame, event)| event.is_empty() || *frame >= horizontal * vertical)
+        {
+            return Err(invalid_data(format!(
+                "animation {} has no frame {} for event {}",
+                name, frame, event
+            )));
+        }
+
+        Ok(AnimationDefinition {
+            name: name.into(),
+            rect: Rect::new(x as i32, y as i32, width, height),
+            horizontal,
+            vertical,
+            durations,
+            play_once,
+            mode,
+            events,
+        })
+    }
+
+    /// 왼쪽 위부터 가로로 읽은 프레임 영역들
+    pub fn get_frames(&self) -> Vec<Rect> {
+        let mut frames = vec![];
+        for y in 0..self.vertical {
+            for x in 0..self.horizontal {
+                frames.push(Rect::new(
+                    self.rect.x + (self.rect.width() * x as u32) as i32,
+                    self.rect.y + (self.rect.height() * y as u32) as i32,
+                    self.rect.width(),
+                    self.rect.height(),
+                ));
+            }
+        }
+        frames
+    }
+
+    /// 프레임 수만큼 늘린 프레임별 시간
+    pub fn get_durations(&self) -> Vec<u32> {
+        let count = self.horizontal * self.vertical;
+        if self.durations.len() == count {
+            self.durations.clone()
+        } else {
+            vec![self.durations[0]; count]
+        }
+    }
+}
+
+/// 스프라이트 하나가 쓰는 애니메이션들
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnimationSet {
+    pub name: String,
+    /// 텍스처 이름
+    pub sheet: String,
+    pub animations: Vec<AnimationDefinition>,
+}
+
+/// 애니메이션 정의 파일의 모든 세트
+/// 파일은 `[세트 이름]` 아래에 `sheet = 텍스처 이름`과 `애니메이션 이름 = 정의`를 한 줄씩 쓴다.
+/// `#`으로 시작하는 줄은 무시한다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnimationSets {
+    pub sets: HashMap<String, AnimationSet>,
+}
+
+impl AnimationSets {
+    /// ASSET_DIR의 ANIMATIONS_FILE을 읽는다.
+    pub fn load() -> Result<AnimationSets> {
+        AnimationSets::from_file(Path::new(&(ASSET_DIR.to_owned() + ANIMATIONS_FILE)))
+    }
+
+    pub fn from_file(path: &Path) -> Result<AnimationSets> {
+        AnimationSets::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<AnimationSets> {
+        let mut sets: HashMap<String, AnimationSet> = HashMap::new();
+        let mut current: Option<String> = None;
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                let name = name.trim().to_string();
+                if sets.contains_key(&name) {
+                    return Err(invalid_data(format!("duplicated animation set: {}", name)));
+                }
+                sets.insert(
+                    name.clone(),
+                    AnimationSet { name: name.clone(), ..Default::default() },
+                );
+                current = Some(name);
+                continue;
+            }
+
+            let set = current
+                .as_ref()
+                .and_then(|name| sets.get_mut(name))
+                .ok_or_else(|| invalid_data(format!("line outside of a set: {}", line)))?;
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| invalid_data(format!("invalid line: {}", line)))?;
+
+            if key == "sheet" {
+                set.sheet = value.into();
+            } else if set.animations.iter().any(|animation| animation.name == key) {
+                return Err(invalid_data(format!("duplicated animation: {}.{}", set.name, key)));
+            } else {
+                set.animations.push(AnimationDefinition::parse(key, value)?);
+            }
+        }
+
+        if let Some(set) = sets.values().find(|set| set.sheet.is_empty()) {
+            return Err(invalid_data(format!("animation set {} has no sheet", set.name)));
+        }
+
+        Ok(AnimationSets { sets })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AnimationSet> {
+        self.sets.get(name)
+    }
+
+    /// name 세트로 AnimateSprite를 만든다.
+    /// 정의 파일에 없는 세트면 경고를 남기고 아무것도 그리지 않는 스프라이트를 돌려준다.
+    pub fn get_sprite(&self, name: &str) -> AnimateSprite {
+        match self.get(name) {
+            Some(set) => AnimateSprite::from_set(set),
+            None => {
+                eprintln!("warning: animation set {} is not defined in {}", name, ANIMATIONS_FILE);
+                AnimateSprite::new(name.into())
+            }
+        }
+    }
+
+    /// REQUIRED_ANIMATION_SETS가 모두 있고, 모든 세트의 텍스처가 읽혀 있고,
+    /// 모든 프레임이 텍스처 안에 있는지 확인한다. 문제가 있으면 모두 모아 한 번에 알려준다.
+    pub fn validate(&self, renderer: &dyn Renderer) -> Result<()> {
+        let mut errors = vec![];
+
+        for name in REQUIRED_ANIMATION_SETS.iter().filter(|name| !self.sets.contains_key(**name)) {
+            errors.push(format!("{}: missing animation set", name));
+        }
+
+        let mut names: Vec<&String> = self.sets.keys().collect();
+        names.sort();
+        for set in names.into_iter().map(|name| &self.sets[name]) {
+            let (width, height) = match renderer.texture_size(&set.sheet) {
+                Some(size) => size,
+                None => {
+                    errors.push(format!("{}: missing sheet {}", set.name, set.sheet));
+                    continue;
+                }
+            };
+
+            for animation in &set.animations {
+                let out_of_bounds = animation.get_frames().into_iter().any(|frame| {
+                    frame.x < 0
+                        || frame.y < 0
+                        || frame.right() as u32 > width
+                        || frame.bottom() as u32 > height
+                });
+                if out_of_bounds {
+                    errors.push(format!(
+                        "{}.{}: frames out of {} ({}x{})",
+                        set.name, animation.name, set.sheet, width, height
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_data(errors.join("\n")))
+        }
+    }
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::RecordingRenderer;
+
+    fn parse_error(text: &str) -> String {
+        let error = AnimationSets::parse(text).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        error.to_string()
+    }
+
+    #[test]
+    fn parses_sets_and_definitions() {
+        let sets = AnimationSets::parse(
+            "# comment\n[bat]\nsheet = enemy\nfly = 32, 32, 16, 16, 3, 1, 100/50/100, pingpong once, flap@1\n",
+        )
+        .unwrap();
+
+        let bat = sets.get("bat").unwrap();
+        assert_eq!(bat.sheet, "enemy");
+        let fly = &bat.animations[0];
+        assert_eq!((fly.mode, fly.play_once), (PlayMode::PingPong, true));
+        assert_eq!(fly.get_durations(), [100, 50, 100]);
+        assert_eq!(fly.get_frames()[2], Rect::new(64, 32, 16, 16));
+        assert_eq!(fly.events, [(1, "flap".to_string())]);
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        parse_error("idle = 0, 0, 16, 16, 1, 1, 100, loop");
+        parse_error("[player]\nidle = 0, 0, 16, 16, 1, 1, 100, loop");
+        parse_error("[player]\nsheet = player\n[player]\nsheet = player");
+        parse_error("[player]\nsheet = player\nidle");
+        parse_error("[player]\nsheet = player\nidle = 0, 0, 16, 16, 1, 1, 100");
+        parse_error("[player]\nsheet = player\nidle = 0, 0, 0, 16, 1, 1, 100, loop");
+        parse_error("[player]\nsheet = player\nidle = 0, 0, 16, 16, 1, 1, 100, sideways");
+
+        let idle = "idle = 0, 0, 16, 16, 1, 1, 100, loop\n";
+        let duplicated = parse_error(&format!("[player]\nsheet = player\n{}{}", idle, idle));
+        assert!(duplicated.contains("duplicated animation: player.idle"));
+
+        let durations =
+            parse_error("[player]\nsheet = player\nmove = 0, 0, 16, 16, 3, 1, 100/50, loop");
+        assert!(durations.contains("3 frames but 2 durations"));
+
+        let event =
+            parse_error("[player]\nsheet = player\nmove = 0, 0, 16, 16, 3, 1, 100, loop, step@3");
+        assert!(event.contains("no frame 3"));
+    }
+
+    #[test]
+    fn validate_reports_missing_sets_and_frames() {
+        let sets = AnimationSets::parse(
+            "[player]\nsheet = player\nidle = 0, 0, 16, 16, 2, 1, 100, loop\n[bat]\nsheet = enemy\n",
+        )
+        .unwrap();
+        let mut renderer = RecordingRenderer::new();
+        renderer.insert_texture("player", 16, 16);
+
+        let error = sets.validate(&renderer).unwrap_err().to_string();
+        assert!(error.contains("impact: missing animation set"));
+        assert!(error.contains("bat: missing sheet enemy"));
+        assert!(error.contains("player.idle: frames out of player (16x16)"));
+    }
+}
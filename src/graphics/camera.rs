@@ -0,0 +1,91 @@
+use sdl2::rect::Rect;
+
+use crate::game::{SCREEN_HEIGHT, SCREEN_WIDTH, SPRITE_SCALE};
+
+/// 바라보는 방향으로 카메라를 앞세우는 거리 (px)
+pub const LOOK_AHEAD_X: f32 = 32.;
+pub const LOOK_AHEAD_Y: f32 = 32.;
+/// 1ms 동안 목표 위치까지 남은 거리 중 따라가는 비율
+pub const CAMERA_SMOOTHING: f32 = 0.004;
+
+/// 플레이어를 따라가는 카메라
+/// x, y는 화면 좌상단의 월드 좌표(논리 좌표)이다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub x: f32,
+    pub y: f32,
+    pub width: u32,
+    pub height: u32,
+    /// 직전 틱의 위치. 렌더링 보간에 쓴다.
+    previous: (f32, f32),
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new(
+            (SCREEN_WIDTH as f32 / SPRITE_SCALE) as u32,
+            (SCREEN_HEIGHT as f32 / SPRITE_SCALE) as u32,
+        )
+    }
+}
+
+impl Camera {
+    pub fn new(width: u32, height: u32) -> Self {
+        Camera { x: 0., y: 0., width, height, previous: (0., 0.) }
+    }
+
+    /// 대상이 화면 가운데에 오도록 (look 방향만큼 앞세워) 했을 때 카메라의 위치
+    fn get_target(&self, target: Rect, look: (i32, i32), bounds: (u32, u32)) -> (f32, f32) {
+        let x = target.center().x() as f32 + look.0 as f32 * LOOK_AHEAD_X - self.width as f32 / 2.;
+        let y = target.center().y() as f32 + look.1 as f32 * LOOK_AHEAD_Y - self.height as f32 / 2.;
+
+        (clamp_axis(x, self.width, bounds.0), clamp_axis(y, self.height, bounds.1))
+    }
+
+    /// 대상을 부드럽게 따라간다.
+    /// look은 바라보는 방향 (-1, 0, 1), bounds는 맵의 픽셀 크기이다.
+    pub fn follow(&mut self, dt: u32, target: Rect, look: (i32, i32), bounds: (u32, u32)) {
+        let (x, y) = self.get_target(target, look, bounds);
+        let ratio = (dt as f32 * CAMERA_SMOOTHING).min(1.);
+
+        self.previous = (self.x, self.y);
+        self.x += (x - self.x) * ratio;
+        self.y += (y - self.y) * ratio;
+    }
+
+    /// 대상 위치로 바로 옮긴다. 맵을 옮겼을 때 사용한다.
+    pub fn snap(&mut self, target: Rect, bounds: (u32, u32)) {
+        let (x, y) = self.get_target(target, (0, 0), bounds);
+        self.x = x;
+        self.y = y;
+        self.previous = (x, y);
+    }
+
+    /// 직전 틱과 현재 틱 사이를 alpha 비율로 보간한 카메라
+    pub fn interpolated(&self, alpha: f32) -> Camera {
+        Camera {
+            x: self.previous.0 + (self.x - self.previous.0) * alpha,
+            y: self.previous.1 + (self.y - self.previous.1) * alpha,
+            previous: self.previous,
+            ..*self
+        }
+    }
+
+    pub fn get_rect(&self) -> Rect {
+        Rect::new(self.x as i32, self.y as i32, self.width, self.height)
+    }
+
+    /// 월드 좌표를 화면 좌표로 바꾼다.
+    pub fn to_screen(&self, x: i32, y: i32) -> (i32, i32) {
+        (x - self.x as i32, y - self.y as i32)
+    }
+}
+
+/// 맵 밖이 보이지 않도록 한다. 맵이 화면보다 작으면 가운데에 둔다.
+fn clamp_axis(position: f32, view: u32, bound: u32) -> f32 {
+    if bound <= view {
+        -((view - bound) as f32 / 2.)
+    } else {
+        position.max(0.).min((bound - view) as f32)
+    }
+}
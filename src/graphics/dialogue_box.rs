@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+
+use sdl2::{pixels::Color, rect::Rect};
+
+use super::{Renderer, Sprite};
+
+/// 글자 하나가 찍히는 시간 (ms)
+pub const CHARACTER_DURATION: u32 = 20;
+/// 커서가 깜빡이는 주기의 절반 (ms)
+pub const CURSOR_BLINK: u32 = 200;
+/// 한 번에 보이는 줄 수
+pub const MAX_LINES: usize = 3;
+pub const LINE_HEIGHT: i32 = 16;
+/// TTF 글꼴 크기
+pub const FONT_SIZE: u16 = 8;
+/// 새 줄로 넘어갈 때 한 글자 시간마다 올라가는 픽셀 수
+const SCROLL_STEP: i32 = 4;
+
+const BOX_X: i32 = 38;
+const BOX_Y: i32 = 174;
+const TEXT_X: i32 = BOX_X + 14;
+const TEXT_Y: i32 = BOX_Y + 10;
+const TEXT_WIDTH: u32 = 216;
+const TEXT_HEIGHT: u32 = 50;
+const FACE_SIZE: u32 = 48;
+const FACE_COLUMNS: u16 = 6;
+const PROMPT_X: i32 = 216;
+const PROMPT_Y: i32 = 144;
+
+/// 대화창
+/// 프레임은 "textbox", 얼굴은 "face" 스프라이트 시트를 사용한다.
+pub struct DialogueBox {
+    frame_top: Sprite,
+    frame_middle: Sprite,
+    frame_bottom: Sprite,
+    prompt: Sprite,
+    prompt_cursor: Sprite,
+    face: Option<Sprite>,
+    lines: Vec<String>,
+    pending: VecDeque<char>,
+    time_elapsed: u32,
+    cursor_time: u32,
+    scrolling: bool,
+    scroll_offset: i32,
+    prompt_selection: Option<bool>,
+    pub visible: bool,
+}
+
+impl Default for DialogueBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DialogueBox {
+    pub fn new() -> Self {
+        DialogueBox {
+            frame_top: Sprite::new("textbox".into(), 0, 0, 244, 8),
+            frame_middle: Sprite::new("textbox".into(), 0, 8, 244, 8),
+            frame_bottom: Sprite::new("textbox".into(), 0, 16, 244, 8),
+            prompt: Sprite::new("textbox".into(), 152, 48, 92, 32),
+            prompt_cursor: Sprite::new("textbox".into(), 112, 88, 16, 16),
+            face: None,
+            lines: vec![String::new()],
+            pending: VecDeque::new(),
+            time_elapsed: 0,
+            cursor_time: 0,
+            scrolling: false,
+            scroll_offset: 0,
+            prompt_selection: None,
+            visible: false,
+        }
+    }
+
+    /// 빈 창을 연다.
+    pub fn open(&mut self) {
+        self.clear();
+        self.visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.clear();
+        self.face = None;
+        self.prompt_selection = None;
+        self.visible = false;
+    }
+
+    /// 출력된 글자와 아직 출력되지 않은 글자를 모두 지운다.
+    pub fn clear(&mut self) {
+        self.lines = vec![String::new()];
+        self.pending.clear();
+        self.scrolling = false;
+        self.scroll_offset = 0;
+        self.time_elapsed = 0;
+    }
+
+    /// Face.pbm의 index 번째 얼굴을 보여준다. None이면 얼굴을 감춘다.
+    pub fn set_face(&mut self, index: Option<u16>) {
+        self.face = index.map(|index| {
+            Sprite::new(
+                "face".into(),
+                ((index % FACE_COLUMNS) as u32 * FACE_SIZE) as i32,
+                ((index / FACE_COLUMNS) as u32 * FACE_SIZE) as i32,
+                FACE_SIZE,
+                FACE_SIZE,
+            )
+        });
+    }
+
+    /// 글자를 출력 대기열에 넣는다. '\n'은 줄바꿈이 된다.
+    pub fn push_text(&mut self, text: &str) {
+        self.pending.extend(text.chars());
+    }
+
+    pub fn new_line(&mut self) {
+        self.pending.push_back('\n');
+    }
+
+    /// 아직 찍을 글자가 남았거나 줄을 올리는 중인가
+    pub fn is_typing(&self) -> bool {
+        !self.pending.is_empty() || self.scrolling
+    }
+
+    /// 남은 글자를 한 번에 모두 찍는다.
+    pub fn skip(&mut self) {
+        while self.is_typing() {
+            self.step();
+        }
+    }
+
+    /// 예/아니오 선택지를 띄운다. 처음에는 예가 선택되어 있다.
+    pub fn show_prompt(&mut self) {
+        self.prompt_selection = Some(true);
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.prompt_selection.is_some()
+    }
+
+    pub fn select(&mut self, yes: bool) {
+        if self.prompt_selection.is_some() {
+            self.prompt_selection = Some(yes);
+        }
+    }
+
+    /// 선택지를 닫고 예를 골랐는지 반환한다.
+    pub fn close_prompt(&mut self) -> bool {
+        self.prompt_selection.take().unwrap_or(false)
+    }
+
+    pub fn update(&mut self, dt: u32) {
+        self.cursor_time = (self.cursor_time + dt) % (CURSOR_BLINK * 2);
+
+        if !self.is_typing() {
+            self.time_elapsed = 0;
+            return;
+        }
+
+        self.time_elapsed += dt;
+        while self.time_elapsed >= CHARACTER_DURATION && self.is_typing() {
+            self.time_elapsed -= CHARACTER_DURATION;
+            self.step();
+        }
+    }
+
+    fn step(&mut self) {
+        if self.scrolling {
+            self.scroll_offset += SCROLL_STEP;
+            if self.scroll_offset >= LINE_HEIGHT {
+                self.lines.remove(0);
+                self.scroll_offset = 0;
+                self.scrolling = false;
+            }
+            return;
+        }
+
+        match self.pending.pop_front() {
+            Some('\n') => {
+                self.lines.push(String::new());
+                self.scrolling = self.lines.len() > MAX_LINES;
+            }
+            Some(glyph) => {
+                if let Some(line) = self.lines.last_mut() {
+                    line.push(glyph);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn get_text_x(&self) -> i32 {
+        if self.face.is_some() {
+            TEXT_X + FACE_SIZE as i32 + 8
+        } else {
+            TEXT_X
+        }
+    }
+
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        if !self.visible {
+            return;
+        }
+
+        self.frame_top.render(BOX_X, BOX_Y, renderer);
+        for i in 1..7 {
+            self.frame_middle.render(BOX_X, BOX_Y + 8 * i, renderer);
+        }
+        self.frame_bottom.render(BOX_X, BOX_Y + 56, renderer);
+
+        if let Some(yes) = self.prompt_selection {
+            self.prompt.render(PROMPT_X, PROMPT_Y, renderer);
+            self.prompt_cursor.render(
+                PROMPT_X - 5 + if yes { 0 } else { 41 },
+                PROMPT_Y + 10,
+                renderer,
+            );
+        }
+
+        if let Some(face) = &self.face {
+            face.render(TEXT_X, TEXT_Y - 2, renderer);
+        }
+
+        let text_x = self.get_text_x();
+        renderer.set_clip_rect(Some(Rect::new(
+            text_x,
+            TEXT_Y,
+            TEXT_WIDTH - (text_x - TEXT_X) as u32,
+            TEXT_HEIGHT,
+        )));
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let y = TEXT_Y + i as i32 * LINE_HEIGHT - self.scroll_offset;
+            renderer.draw_text(line, text_x, y, FONT_SIZE, Color::WHITE);
+        }
+
+        // 커서는 마지막 글자 뒤에서 깜빡인다.
+        if self.cursor_time < CURSOR_BLINK && !self.scrolling {
+            let last_line = self.lines.last().map_or("", |line| line.as_str());
+            let length = renderer.measure_text(last_line, FONT_SIZE) as i32;
+            let y = TEXT_Y + (self.lines.len() as i32 - 1) * LINE_HEIGHT;
+
+            renderer.fill_rect(Rect::new(text_x + length, y, 5, FONT_SIZE as u32), Color::WHITE);
+        }
+
+        renderer.set_clip_rect(None);
+    }
+}
@@ -1,13 +1,35 @@
-use super::Rectangle;
+use crate::player::Direction;
+
+use super::{Rectangle, Vector2};
 
 #[derive(Clone, Debug)]
 pub struct Door {
     pub destination: String,
+    /// 도착한 맵에서 나타날 입구 이름. 없으면 맵의 start 위치에 나타난다.
+    pub entrance: Option<String>,
     pub position: Rectangle,
 }
 
 impl Door {
-    pub fn new(destination: String, position: Rectangle) -> Self {
-        Door { destination, position }
+    pub fn new(destination: String, entrance: Option<String>, position: Rectangle) -> Self {
+        Door { destination, entrance, position }
     }
 }
+
+/// 플레이어가 닿거나 살펴보면 시작되는 TSC 이벤트
+#[derive(Clone, Debug)]
+pub struct EventTrigger {
+    pub event: u16,
+    pub position: Rectangle,
+    /// 닿기만 해도 시작된다. false이면 아래 키로 살펴봐야 시작된다.
+    pub on_touch: bool,
+}
+
+/// 문을 지나 맵에 들어왔을 때 플레이어가 나타나는 자리
+#[derive(Clone, Debug)]
+pub struct Entrance {
+    pub name: String,
+    pub position: Vector2,
+    /// 나타날 때 바라보는 방향. 없으면 들어오기 전 방향을 그대로 쓴다.
+    pub facing: Option<Direction>,
+}
@@ -0,0 +1,89 @@
+use sdl2::pixels::Color;
+
+use super::{AnimateSprite, AnimationSets, Camera, Renderable, Renderer, FONT_SIZE};
+
+/// 피해량 숫자가 떠오르는 시간과 전체 표시 시간 (ms)
+pub const DAMAGE_NUMBER_RISE_TIME: u32 = 400;
+pub const DAMAGE_NUMBER_DURATION: u32 = 1000;
+/// 피해량 숫자가 떠오르는 높이 (px)
+pub const DAMAGE_NUMBER_RISE: u32 = 12;
+
+/// 한 번 재생되고 사라지는 효과
+#[derive(Clone)]
+pub struct Effect {
+    animation: AnimateSprite,
+    /// 효과 중심의 위치 (px)
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Effect {
+    /// 총알이 벽이나 적에 맞았을 때 퍼지는 불꽃
+    pub fn impact(animations: &AnimationSets, x: i32, y: i32) -> Self {
+        Effect { animation: animations.get_sprite("impact"), x, y }
+    }
+
+    /// 적이 죽을 때 나는 연기
+    pub fn smoke(animations: &AnimationSets, x: i32, y: i32) -> Self {
+        Effect { animation: animations.get_sprite("smoke"), x, y }
+    }
+
+    pub fn update(&mut self, dt: u32) {
+        self.animation.update(dt);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.animation.is_finished()
+    }
+}
+
+impl Renderable for Effect {
+    fn get_name(&self) -> String {
+        "caret".into()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, _alpha: f32) {
+        let (x, y) = camera.to_screen(self.x - 8, self.y - 8);
+        self.animation.render(x, y, renderer);
+    }
+}
+
+/// 맞은 자리에서 떠올랐다가 사라지는 피해량
+#[derive(Clone, Debug)]
+pub struct DamageNumber {
+    pub value: i32,
+    /// 숫자 아래 가운데의 위치 (px)
+    pub x: i32,
+    pub y: i32,
+    elapsed: u32,
+}
+
+impl DamageNumber {
+    pub fn new(value: i32, x: i32, y: i32) -> Self {
+        DamageNumber { value, x, y, elapsed: 0 }
+    }
+
+    pub fn update(&mut self, dt: u32) {
+        self.elapsed += dt;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= DAMAGE_NUMBER_DURATION
+    }
+}
+
+impl Renderable for DamageNumber {
+    fn get_name(&self) -> String {
+        "damage".into()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, _alpha: f32) {
+        let text = format!("-{}", self.value);
+        let width = renderer.measure_text(&text, FONT_SIZE) as i32;
+        let rise = self.elapsed.min(DAMAGE_NUMBER_RISE_TIME) * DAMAGE_NUMBER_RISE
+            / DAMAGE_NUMBER_RISE_TIME;
+
+        let (x, y) = camera.to_screen(self.x - width / 2, self.y - FONT_SIZE as i32 - rise as i32);
+        renderer.draw_text(&text, x, y, FONT_SIZE, Color::RED);
+    }
+}
@@ -0,0 +1,231 @@
+use std::cell::RefCell;
+use std::collections::{hash_map::Entry, HashMap};
+use std::path::{Path, PathBuf};
+
+use sdl2::{
+    pixels::Color,
+    rect::Rect,
+    render::{Texture, TextureCreator, WindowCanvas},
+    ttf::{Font, Sdl2TtfContext},
+    video::WindowContext,
+};
+
+use crate::game::SPRITE_SCALE;
+
+/// measure로 잰 폭이 max_width를 넘지 않도록 줄을 나눈다.
+/// 공백이 있으면 마지막 공백에서, 없으면(한글/일본어 등) 글자 단위로 나눈다.
+pub fn wrap_text(text: &str, max_width: u32, measure: impl Fn(&str) -> u32) -> Vec<String> {
+    let mut lines = vec![];
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for glyph in paragraph.chars() {
+            let mut candidate = line.clone();
+            candidate.push(glyph);
+
+            if !line.is_empty() && measure(&candidate) > max_width {
+                // 넘치는 글자가 공백이면 그 자리에서 나눈다.
+                if glyph == ' ' {
+                    lines.push(std::mem::take(&mut line));
+                    continue;
+                }
+                match line.rfind(' ') {
+                    Some(space) if space > 0 => {
+                        let rest = line[space + 1..].to_string();
+                        line.truncate(space);
+                        lines.push(line);
+                        line = rest;
+                    }
+                    _ => lines.push(std::mem::take(&mut line)),
+                }
+                line.push(glyph);
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// 글자 하나를 그린 텍스처와 다음 글자까지의 거리
+struct Glyph<'a> {
+    texture: Option<Texture<'a>>,
+    advance: i32,
+}
+
+/// TTF 글꼴과 글자 텍스처 캐시
+/// 크기는 논리 좌표(SPRITE_SCALE 적용 전) 기준이며, 실제로는 확대된 크기로 그려서 선명하게 보이도록 한다.
+#[derive(Default)]
+pub struct FontCache<'a> {
+    ttf_context: Option<&'a Sdl2TtfContext>,
+    texture_creator: Option<&'a TextureCreator<WindowContext>>,
+    path: PathBuf,
+    fonts: RefCell<HashMap<u16, Font<'a, 'static>>>,
+    glyphs: RefCell<HashMap<(char, u16, u32), Glyph<'a>>>,
+}
+
+impl<'a> FontCache<'a> {
+    pub fn new() -> FontCache<'a> {
+        Self::default()
+    }
+
+    /// 글꼴 파일을 지정한다. 이전 글꼴로 만든 글자 텍스처는 모두 버린다.
+    pub fn load(
+        &mut self,
+        ttf_context: &'a Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        path: &Path,
+    ) -> Result<(), String> {
+        // 글꼴 파일이 제대로 열리는지 미리 확인한다.
+        ttf_context.load_font(path, 8)?;
+
+        self.ttf_context = Some(ttf_context);
+        self.texture_creator = Some(texture_creator);
+        self.path = path.to_path_buf();
+        self.fonts.borrow_mut().clear();
+        self.glyphs.borrow_mut().clear();
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.ttf_context.is_some()
+    }
+
+    fn point_size(size: u16) -> u16 {
+        (size as f32 * SPRITE_SCALE) as u16
+    }
+
+    /// size 크기의 글꼴로 f를 실행한다. 처음 쓰는 크기라면 글꼴을 연다.
+    fn with_font<R>(&self, size: u16, f: impl FnOnce(&Font<'a, 'static>) -> R) -> Option<R> {
+        let ttf_context = self.ttf_context?;
+        let mut fonts = self.fonts.borrow_mut();
+        if let Entry::Vacant(entry) = fonts.entry(size) {
+            entry.insert(ttf_context.load_font(&self.path, Self::point_size(size)).ok()?);
+        }
+        fonts.get(&size).map(f)
+    }
+
+    fn with_glyph<R>(
+        &self,
+        glyph: char,
+        size: u16,
+        color: Color,
+        f: impl FnOnce(&Glyph<'a>) -> R,
+    ) -> Option<R> {
+        let key = (glyph, size, u32::from_be_bytes([color.r, color.g, color.b, color.a]));
+
+        if !self.glyphs.borrow().contains_key(&key) {
+            let texture_creator = self.texture_creator?;
+            let new_glyph = self.with_font(size, |font| {
+                let advance = font.find_glyph_metrics(glyph).map_or(0, |metrics| metrics.advance);
+                // 공백처럼 그릴 것이 없는 글자는 텍스처 없이 거리만 가진다.
+                let texture =
+                    font.render_char(glyph).blended(color).ok().and_then(|surface| {
+                        texture_creator.create_texture_from_surface(surface).ok()
+                    });
+                Glyph { texture, advance }
+            })?;
+            self.glyphs.borrow_mut().insert(key, new_glyph);
+        }
+
+        self.glyphs.borrow().get(&key).map(f)
+    }
+
+    /// 논리 좌표 기준 글자 줄의 (폭, 높이)
+    pub fn measure(&self, text: &str, size: u16) -> (u32, u32) {
+        self.with_font(size, |font| {
+            let width: i32 = text
+                .chars()
+                .map(|glyph| font.find_glyph_metrics(glyph).map_or(0, |metrics| metrics.advance))
+                .sum();
+            (
+                (width.max(0) as f32 / SPRITE_SCALE) as u32,
+                (font.height().max(0) as f32 / SPRITE_SCALE) as u32,
+            )
+        })
+        .unwrap_or((0, 0))
+    }
+
+    /// max_width(논리 좌표)를 넘지 않도록 줄을 나눈다.
+    pub fn wrap(&self, text: &str, size: u16, max_width: u32) -> Vec<String> {
+        wrap_text(text, max_width, |line| self.measure(line, size).0)
+    }
+
+    /// (x, y) 논리 좌표에 한 줄을 그리고 그린 폭을 반환한다.
+    pub fn render(
+        &self,
+        canvas: &mut WindowCanvas,
+        text: &str,
+        x: i32,
+        y: i32,
+        size: u16,
+        color: Color,
+    ) -> u32 {
+        let start_x = (x as f32 * SPRITE_SCALE) as i32;
+        let top = (y as f32 * SPRITE_SCALE) as i32;
+        let mut pen_x = start_x;
+
+        for glyph in text.chars() {
+            let advance = self.with_glyph(glyph, size, color, |glyph| {
+                if let Some(texture) = &glyph.texture {
+                    let query = texture.query();
+                    canvas
+                        .copy(texture, None, Some(Rect::new(pen_x, top, query.width, query.height)))
+                        .unwrap();
+                }
+                glyph.advance
+            });
+            pen_x += advance.unwrap_or(0);
+        }
+
+        ((pen_x - start_x) as f32 / SPRITE_SCALE) as u32
+    }
+
+    /// max_width에 맞춰 줄을 나누어 그리고 그린 높이를 반환한다.
+    pub fn render_wrapped(
+        &self,
+        canvas: &mut WindowCanvas,
+        text: &str,
+        position: (i32, i32),
+        max_width: u32,
+        size: u16,
+        color: Color,
+    ) -> u32 {
+        let (x, y) = position;
+        let line_height = self.measure("", size).1;
+        let lines = self.wrap(text, size, max_width);
+
+        for (i, line) in lines.iter().enumerate() {
+            self.render(canvas, line, x, y + (i as u32 * line_height) as i32, size, color);
+        }
+
+        lines.len() as u32 * line_height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, max_width: u32) -> Vec<String> {
+        wrap_text(text, max_width, |line| line.chars().count() as u32 * 8)
+    }
+
+    #[test]
+    fn wraps_at_last_space() {
+        assert_eq!(wrap("hello cave story", 80), ["hello cave", "story"]);
+    }
+
+    #[test]
+    fn wraps_by_glyph_without_spaces() {
+        assert_eq!(wrap("동굴이야기입니다", 32), ["동굴이야", "기입니다"]);
+    }
+
+    #[test]
+    fn keeps_line_breaks() {
+        assert_eq!(wrap("a\nb", 80), ["a", "b"]);
+        assert_eq!(wrap("", 80), [""]);
+    }
+}
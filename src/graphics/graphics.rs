@@ -1,38 +1,145 @@
+use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::image::LoadTexture;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
 use sdl2::render::WindowCanvas;
 use sdl2::render::{Texture, TextureCreator};
+use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::WindowContext;
 use std::collections::HashMap;
 use std::path::Path;
 
-use super::Renderable;
+use crate::game::SPRITE_SCALE;
 
-#[derive(Default)]
+use super::{draw_wrapped_lines, FontCache, Renderer, Transform, GLYPH_WIDTH};
+
+/// SDL 창에 그리는 Renderer
 pub struct Graphics<'a> {
+    pub canvas: WindowCanvas,
+    texture_creator: &'a TextureCreator<WindowContext>,
     pub sprite_sheets: HashMap<String, Texture<'a>>,
+    pub font: FontCache<'a>,
+}
+
+/// 논리 좌표의 영역을 화면 좌표로 확대한다.
+fn to_screen(rect: Rect) -> Rect {
+    Rect::new(
+        (rect.x() as f32 * SPRITE_SCALE) as i32,
+        (rect.y() as f32 * SPRITE_SCALE) as i32,
+        (rect.width() as f32 * SPRITE_SCALE) as u32,
+        (rect.height() as f32 * SPRITE_SCALE) as u32,
+    )
 }
 
 impl<'a> Graphics<'a> {
-    pub fn new() -> Graphics<'a> {
-        Graphics { sprite_sheets: HashMap::new() }
+    pub fn new(
+        canvas: WindowCanvas,
+        texture_creator: &'a TextureCreator<WindowContext>,
+    ) -> Graphics<'a> {
+        Graphics { canvas, texture_creator, sprite_sheets: HashMap::new(), font: FontCache::new() }
     }
 
-    pub fn load_image(
+    /// 글자 출력에 쓸 TTF 글꼴을 읽는다.
+    pub fn load_font(
         &mut self,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        image_name: String,
+        ttf_context: &'a Sdl2TtfContext,
         path: &Path,
-    ) -> &Texture<'a> {
-        if let Ok(texture) = texture_creator.load_texture(&path) {
-            self.sprite_sheets.insert(image_name.clone(), texture);
+    ) -> Result<(), String> {
+        self.font.load(ttf_context, self.texture_creator, path)
+    }
+}
+
+impl<'a> Renderer for Graphics<'a> {
+    fn load_texture(&mut self, name: &str, path: &Path) -> Result<(u32, u32), String> {
+        let texture = self.texture_creator.load_texture(path)?;
+        let query = texture.query();
+        self.sprite_sheets.insert(name.into(), texture);
+
+        Ok((query.width, query.height))
+    }
+
+    fn texture_size(&self, name: &str) -> Option<(u32, u32)> {
+        self.sprite_sheets.get(name).map(|texture| {
+            let query = texture.query();
+            (query.width, query.height)
+        })
+    }
+
+    fn copy(&mut self, name: &str, src: Rect, dest: Rect, transform: Transform) {
+        if let Some(texture) = self.sprite_sheets.get(name) {
+            self.canvas
+                .copy_ex(
+                    texture,
+                    Some(src),
+                    Some(to_screen(dest)),
+                    transform.angle,
+                    None,
+                    transform.flip_horizontal,
+                    transform.flip_vertical,
+                )
+                .unwrap();
+        }
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let draw_color = self.canvas.draw_color();
+        self.canvas.set_draw_color(color);
+        self.canvas.fill_rect(to_screen(rect)).unwrap();
+        self.canvas.set_draw_color(draw_color);
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<Rect>) {
+        self.canvas.set_clip_rect(rect.map(to_screen));
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, size: u16, color: Color) -> u32 {
+        if self.font.is_loaded() {
+            return self.font.render(&mut self.canvas, text, x, y, size, color);
         }
 
-        self.sprite_sheets.get(&image_name).unwrap()
+        // TTF 글꼴이 없으면 SDL2_gfx의 8x8 글꼴을 논리 좌표에 그린 뒤 확대한다.
+        self.canvas.set_scale(SPRITE_SCALE, SPRITE_SCALE).unwrap();
+        self.canvas.string(x as i16, y as i16, text, color).unwrap();
+        self.canvas.set_scale(1., 1.).unwrap();
+
+        self.measure_text(text, size)
     }
 
-    pub fn render_sprite(&self, canvas: &mut WindowCanvas, sprite: &dyn Renderable) {
-        if let Some(texture) = self.sprite_sheets.get(&sprite.get_name()) {
-            sprite.render(canvas, texture);
+    fn measure_text(&self, text: &str, size: u16) -> u32 {
+        if self.font.is_loaded() {
+            self.font.measure(text, size).0
+        } else {
+            text.chars().count() as u32 * GLYPH_WIDTH as u32
         }
     }
+
+    fn draw_text_wrapped(
+        &mut self,
+        text: &str,
+        position: (i32, i32),
+        max_width: u32,
+        size: u16,
+        color: Color,
+    ) -> u32 {
+        if self.font.is_loaded() {
+            return self.font.render_wrapped(
+                &mut self.canvas,
+                text,
+                position,
+                max_width,
+                size,
+                color,
+            );
+        }
+
+        draw_wrapped_lines(self, text, position, max_width, size, color)
+    }
+
+    fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    fn present(&mut self) {
+        self.canvas.present();
+    }
 }
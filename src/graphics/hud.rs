@@ -1,7 +1,13 @@
 use crate::player::Player;
 
-use super::{Renderable, Sprite, Vector2};
-use sdl2::render::{Texture, WindowCanvas};
+use sdl2::rect::Rect;
+
+use super::{Camera, Renderable, Renderer, Sprite, Transform, Vector2};
+
+/// 경험치 바의 폭 (px)
+pub const EXP_BAR_WIDTH: u32 = 40;
+/// 무기 레벨이 바뀌었을 때 경험치 바가 깜빡이는 시간 (ms)
+pub const EXP_FLASH_DURATION: u32 = 500;
 
 pub struct Hud {
     health_bar: Sprite,
@@ -17,6 +23,16 @@ pub struct Hud {
     lvl_number_pos: Vector2,
     exp_bar: Sprite,
     exp_bar_pos: Vector2,
+    exp_fill: Sprite,
+    exp_max: Sprite,
+    exp_flash: Sprite,
+    /// 경험치 바에서 채워진 폭 (px). exp_fill은 가득 찬 바이고 이 폭만큼만 그린다.
+    exp_width: u32,
+    /// 현재 무기 레벨. 무기가 없으면 0
+    level: usize,
+    is_max: bool,
+    /// 남은 깜빡임 시간 (ms)
+    flash: u32,
     slash: Sprite,
     slash_pos: Vector2,
     dashes: Sprite,
@@ -37,8 +53,11 @@ impl Hud {
         let current_health_pos = Vector2(40., 36.);
 
         let lvl_word = Sprite::new("textbox".into(), 81, 81, 11, 7);
-        let lvl_number = Sprite::new("textbox".into(), 0, 56, 8, 1);
-        let exp_bar = Sprite::new("textbox".into(), 0, 72, 40, 8);
+        let lvl_number = Sprite::new("textbox".into(), 0, 56, 8, 8);
+        let exp_bar = Sprite::new("textbox".into(), 0, 72, EXP_BAR_WIDTH, 8);
+        let exp_fill = Sprite::new("textbox".into(), 0, 80, EXP_BAR_WIDTH, 8);
+        let exp_max = Sprite::new("textbox".into(), 40, 72, EXP_BAR_WIDTH, 8);
+        let exp_flash = Sprite::new("textbox".into(), 40, 80, EXP_BAR_WIDTH, 8);
         let slash = Sprite::new("textbox".into(), 72, 48, 8, 8);
         let dashes = Sprite::new("textbox".into(), 81, 51, 15, 11);
 
@@ -63,6 +82,13 @@ impl Hud {
             lvl_number_pos,
             exp_bar,
             exp_bar_pos,
+            exp_fill,
+            exp_max,
+            exp_flash,
+            exp_width: 0,
+            level: 0,
+            is_max: false,
+            flash: 0,
             slash,
             slash_pos,
             dashes,
@@ -70,12 +96,27 @@ impl Hud {
         }
     }
 
-    pub fn update(&mut self, player: &Player) {
+    pub fn update(&mut self, dt: u32, player: &Player) {
         let max_health = player.max_health;
         self.health = player.current_health;
 
         let ratio: f32 = self.health as f32 / max_health as f32;
         self.current_health_bar.source_rect.set_width((ratio * 39.) as u32);
+
+        self.flash = self.flash.saturating_sub(dt);
+
+        let level = player.get_weapon().map_or(0, |weapon| weapon.level);
+        if self.level != 0 && level != self.level {
+            self.flash = EXP_FLASH_DURATION;
+        }
+        self.level = level;
+        self.lvl_number.source_rect.set_x(8 * level as i32);
+
+        if let Some(weapon) = player.get_weapon() {
+            self.is_max = weapon.is_max();
+            let ratio = weapon.get_experience_ratio().clamp(0., 1.);
+            self.exp_width = (ratio * EXP_BAR_WIDTH as f32) as u32;
+        }
     }
 }
 
@@ -84,12 +125,11 @@ impl Renderable for Hud {
         "textbox".into()
     }
 
-    fn render(&self, canvas: &mut WindowCanvas, texture: &Texture) {
+    fn render(&self, renderer: &mut dyn Renderer, _camera: &Camera, _alpha: f32) {
         self.health_bar.render(
             self.health_bar_pos.0 as i32,
             self.health_bar_pos.1 as i32,
-            canvas,
-            texture,
+            renderer,
         );
 
         for i in 0..self.health.to_string().len() {
@@ -97,36 +137,87 @@ impl Renderable for Hud {
             self.health_digit.get(digit as usize).unwrap().render(
                 (self.health_digit_pos.0 + ((i as f32) * 8.)) as i32,
                 self.health_digit_pos.1 as i32,
-                canvas,
-                texture,
+                renderer,
             );
         }
 
         self.current_health_bar.render(
             self.current_health_pos.0 as i32,
             self.current_health_pos.1 as i32,
-            canvas,
-            texture,
+            renderer,
         );
 
         self.lvl_number.render(
             self.lvl_number_pos.0 as i32,
             self.lvl_number_pos.1 as i32,
-            canvas,
-            texture,
+            renderer,
         );
 
-        self.lvl_word.render(
-            self.lvl_word_pos.0 as i32,
-            self.lvl_word_pos.1 as i32,
-            canvas,
-            texture,
-        );
+        self.lvl_word.render(self.lvl_word_pos.0 as i32, self.lvl_word_pos.1 as i32, renderer);
+
+        self.exp_bar.render(self.exp_bar_pos.0 as i32, self.exp_bar_pos.1 as i32, renderer);
+
+        // 무기가 없으면 빈 바만 그린다.
+        if self.level != 0 {
+            if self.is_max {
+                self.exp_max.render(self.exp_bar_pos.0 as i32, self.exp_bar_pos.1 as i32, renderer);
+            } else if self.exp_width > 0 {
+                // 채워진 폭만큼만 잘라 그린다. Rect는 폭 0을 1로 바꾸므로 0이면 그리지 않는다.
+                let source = self.exp_fill.source_rect;
+                let (x, y) = (self.exp_bar_pos.0 as i32, self.exp_bar_pos.1 as i32);
+                renderer.copy(
+                    &self.exp_fill.name,
+                    Rect::new(source.x(), source.y(), self.exp_width, source.height()),
+                    Rect::new(x, y, self.exp_width, source.height()),
+                    Transform::default(),
+                );
+            }
+        }
+
+        if self.flash > 0 && (self.flash / 50).is_multiple_of(2) {
+            self.exp_flash.render(self.exp_bar_pos.0 as i32, self.exp_bar_pos.1 as i32, renderer);
+        }
 
-        self.exp_bar.render(self.exp_bar_pos.0 as i32, self.exp_bar_pos.1 as i32, canvas, texture);
+        self.slash.render(self.slash_pos.0 as i32, self.slash_pos.1 as i32, renderer);
 
-        self.slash.render(self.slash_pos.0 as i32, self.slash_pos.1 as i32, canvas, texture);
+        self.dashes.render(self.dashes_pos.0 as i32, self.dashes_pos.1 as i32, renderer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{AnimationSets, DrawCommand, RecordingRenderer};
+
+    fn exp_fill_width(hud: &Hud) -> Option<u32> {
+        let mut renderer = RecordingRenderer::new();
+        renderer.insert_texture("textbox", 256, 256);
+        hud.render(&mut renderer, &Camera::default(), 1.0);
+
+        let fill = hud.exp_fill.source_rect;
+        renderer.copies("textbox").iter().find_map(|command| match command {
+            DrawCommand::Copy { src, .. } if (src.x(), src.y()) == (fill.x(), fill.y()) => {
+                Some(src.width())
+            }
+            _ => None,
+        })
+    }
 
-        self.dashes.render(self.dashes_pos.0 as i32, self.dashes_pos.1 as i32, canvas, texture);
+    #[test]
+    fn exp_fill_follows_experience() {
+        let mut player = Player::new(0, 0, &AnimationSets::default());
+        let mut hud = Hud::new();
+        hud.update(0, &player);
+        assert_eq!(exp_fill_width(&hud), None);
+
+        let max_experience = player.weapons[0].get_level().max_experience;
+        player.weapons[0].add_experience(max_experience / 2);
+        hud.update(0, &player);
+        assert_eq!(exp_fill_width(&hud), Some(EXP_BAR_WIDTH / 2));
+
+        // 경험치를 모두 잃으면 이전 폭이 남지 않고 그려지지 않는다.
+        player.weapons[0].lose_experience(max_experience);
+        hud.update(0, &player);
+        assert_eq!(exp_fill_width(&hud), None);
     }
 }
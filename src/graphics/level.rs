@@ -1,19 +1,22 @@
 use sdl2::rect::Rect;
-use sdl2::render::WindowCanvas;
 
 use std::collections::HashMap;
 
 use crate::constant::*;
-use crate::game::SPRITE_SCALE;
 use crate::graphics::tile;
 use crate::physics::collides_with;
-use crate::player::{Bat, Enemy, EnemyRenderable, Player};
-use sdl2::video::WindowContext;
-use sdl2::{image::LoadTexture, render::Texture, render::TextureCreator};
+use crate::player::{Direction, Enemy, EnemyRegistry, EnemyRenderable, NpcTable, Player};
+use std::io::{Error, ErrorKind, Result};
 use std::path::Path;
 use tiled::{parse_file, Frame, PropertyValue};
 
-use super::{AnimatedTile, Door, Graphics, Rectangle, Vector2};
+use super::pxa::{Pxa, SlopeKind, TileAttribute};
+use super::pxe::{Pxe, PxeEntity};
+use super::pxm::Pxm;
+use super::{
+    AnimatedTile, AnimationSets, Camera, Door, Entrance, EventTrigger, Rectangle, Renderer,
+    Transform, Vector2,
+};
 
 /// 맵의 가로 타일 수
 pub const MAP_WIDTH: i32 = 20;
@@ -21,14 +24,87 @@ pub const MAP_WIDTH: i32 = 20;
 /// 맵의 세로 타일 수
 pub const MAP_HEIGHT: i32 = 16;
 
+/// 원본 스테이지 타일 한 개의 폭/높이
+pub const PXM_TILE_SIZE: u32 = 16;
+
+/// enemies 오브젝트를 적으로 만든다.
+/// 오브젝트 이름, 타입 순으로 등록된 적을 찾고, 없으면 npc_type 속성의 원본 NPC 번호로 찾는다.
+fn spawn_object(
+    registry: &EnemyRegistry,
+    object: &tiled::Object,
+    npc_table: &NpcTable,
+    animations: &AnimationSets,
+) -> Option<Box<dyn EnemyRenderable>> {
+    let (x, y) = (object.x as i32, object.y as i32);
+
+    for name in [&object.name, &object.obj_type] {
+        if let Some(constructor) = registry.get_by_name(name) {
+            return Some(constructor(x, y, npc_table, animations));
+        }
+    }
+
+    if let Some(PropertyValue::IntValue(npc_type)) = object.properties.get("npc_type") {
+        return registry.spawn_by_npc_type(*npc_type as u16, x, y, npc_table, animations);
+    }
+
+    registry.spawn_by_name(&object.name, x, y, npc_table, animations)
+}
+
+/// npc.tbl을 읽는다. 파일이 없으면 빈 테이블을 쓴다.
+fn load_npc_table() -> NpcTable {
+    NpcTable::from_file(Path::new(&(ASSET_DIR.to_owned() + "npc.tbl"))).unwrap_or_default()
+}
+
+/// 무한 맵 레이어들의 청크가 차지하는 타일 영역 (left, top, right, bottom)
+/// 유한 맵이거나 청크가 하나도 없으면 None
+fn chunk_bounds(layers: &[tiled::Layer]) -> Option<(i32, i32, i32, i32)> {
+    layers
+        .iter()
+        .filter_map(|layer| match &layer.tiles {
+            tiled::LayerData::Infinite(chunks) => Some(chunks.values()),
+            _ => None,
+        })
+        .flatten()
+        .map(|chunk| {
+            (chunk.x, chunk.y, chunk.x + chunk.width as i32, chunk.y + chunk.height as i32)
+        })
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+}
+
+/// 무한 맵의 청크들을 (dx, dy) 타일만큼 옮긴다.
+fn offset_chunks(layer: &mut tiled::Layer, dx: i32, dy: i32) {
+    if let tiled::LayerData::Infinite(chunks) = &mut layer.tiles {
+        *chunks = chunks
+            .drain()
+            .map(|(_, mut chunk)| {
+                chunk.x += dx;
+                chunk.y += dy;
+                ((chunk.x, chunk.y), chunk)
+            })
+            .collect();
+    }
+}
+
 /// 기울기용 기조체
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Slope {
     pub from: Vector2,
     pub to: Vector2,
+    pub ceiling: bool,
 }
 
 impl Slope {
+    /// PXA의 기울기 속성을 가진 타일 영역으로부터 기울기를 만든다.
+    pub fn from_attribute(kind: SlopeKind, tile: Rect) -> Slope {
+        let (left, right) = kind.half_heights();
+        let half = tile.height() as f32 / 2.;
+        Slope {
+            from: Vector2(tile.left() as f32, tile.top() as f32 + left as f32 * half),
+            to: Vector2(tile.right() as f32, tile.top() as f32 + right as f32 * half),
+            ceiling: kind.is_ceiling(),
+        }
+    }
+
     pub fn get_slope(&self) -> f32 {
         (self.to.top().abs() - self.from.top().abs())
             / (self.to.left().abs() - self.from.left().abs())
@@ -57,7 +133,7 @@ impl Slope {
 /// 지도용 구조체
 /// 지도에는 map용 파일과
 /// 각 map 블럭에 대한 정보를 넣는다.
-pub struct Level<'a> {
+pub struct Level {
     pub x: i32,     //  x
     pub y: i32,     //  y
     pub cam_x: i32, // camera_x
@@ -70,22 +146,42 @@ pub struct Level<'a> {
     pub tile_widths: HashMap<usize, u32>, // width of a tile in pixels
     pub tile_heights: HashMap<usize, u32>, // height of a tile in pixels
     pub layers: Vec<tiled::Layer>,
-    pub textures: HashMap<usize, Texture<'a>>,
+    /// 타일셋 번호별로 Renderer에 등록한 텍스처 이름
+    pub textures: HashMap<usize, String>,
     pub blocks: Vec<Rect>,
     pub slopes: Vec<Slope>,
+    /// PXA로부터 읽은 칸별 타일 속성 (width * height). Tiled 지도는 비어있다.
+    pub attributes: Vec<TileAttribute>,
     pub gids: HashMap<u32, usize>,
     pub animations: HashMap<u32, AnimatedTile>,
     pub start_pos: Vector2,
     pub doors: Vec<Door>,
+    /// 다른 맵의 문에서 이름으로 가리키는 입구들
+    pub entrances: Vec<Entrance>,
+    /// 닿거나 살펴보면 시작되는 이벤트
+    pub triggers: Vec<EventTrigger>,
+    /// 이 맵의 스크립트 `Stage/<stage>.tsc`의 이름. 없으면 Head.tsc만 쓴다.
+    pub stage: Option<String>,
+    /// <TRA가 아닌 길로 들어왔을 때 시작되는 이벤트
+    pub entry_event: Option<u16>,
     pub enemies: Vec<Box<dyn EnemyRenderable>>,
+    pub npc_table: NpcTable,
+    /// enemies 오브젝트와 PXE 엔티티로 적을 만들 때 쓴다.
+    pub enemy_registry: EnemyRegistry,
 }
 
-impl<'a> Level<'a> {
-    pub fn new(texture_creator: &'a TextureCreator<WindowContext>, path: String) -> Level<'a> {
+impl Level {
+    /// Tiled 지도(.tmx)를 읽는다. 지도나 타일셋 이미지를 읽을 수 없으면 에러를 돌려준다.
+    pub fn new(
+        renderer: &mut dyn Renderer,
+        path: String,
+        animation_sets: &AnimationSets,
+    ) -> Result<Level> {
         // read tmx file
-        let map: tiled::Map = parse_file(Path::new(&(ASSET_DIR.to_owned() + &path))).unwrap();
+        let map: tiled::Map = parse_file(Path::new(&(ASSET_DIR.to_owned() + &path)))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {:?}", path, e)))?;
 
-        let layers: Vec<tiled::Layer> = map.layers;
+        let mut layers: Vec<tiled::Layer> = map.layers;
         let object_group: Vec<tiled::ObjectGroup> = map.object_groups;
         let tile_sets: Vec<tiled::Tileset> = map.tilesets;
 
@@ -102,20 +198,25 @@ impl<'a> Level<'a> {
 
         let mut start_pos: Vector2 = Vector2(0., 0.);
         let mut doors: Vec<Door> = vec![];
+        let mut entrances: Vec<Entrance> = vec![];
+        let mut triggers: Vec<EventTrigger> = vec![];
         let mut enemies: Vec<Box<dyn EnemyRenderable>> = vec![];
+        let npc_table = load_npc_table();
+        let enemy_registry = EnemyRegistry::default();
 
         for (i, tileset) in tile_sets.iter().enumerate() {
             let tile_width = tileset.tile_width;
             let tile_height = tileset.tile_height;
 
-            let texture = texture_creator
-                .load_texture(Path::new(&(ASSET_DIR.to_owned() + &tileset.images[0].source)))
-                .unwrap();
+            let texture = tileset.images[0].source.clone();
+            let texture_size = renderer
+                .load_texture(&texture, Path::new(&(ASSET_DIR.to_owned() + &texture)))
+                .map_err(|e| Error::new(ErrorKind::NotFound, e))?;
 
             // tile_atlas는 현재 tileset의 Texture를 tile 한 개의 폭, 높이로 잘라
             // first_gid 부터 다음 tileset의 first_gid 까지를 전체 크기로 하는 타일 정보를 만든다.
             let tile_atlas =
-                tile::TileAtlas::new(&texture, tileset.first_gid, tile_width, tile_height);
+                tile::TileAtlas::new(texture_size, tileset.first_gid, tile_width, tile_height);
             textures.insert(i, texture);
 
             // tile_atlas를 만들고, gid가 주어졌을 때 어떤 tile_atlas에서 부터 찾아야할지
@@ -150,22 +251,48 @@ impl<'a> Level<'a> {
             }
         }
 
+        // 무한 맵은 청크가 음수 좌표에도 있을 수 있다.
+        // 가장 왼쪽 위 청크가 (0, 0)에 오도록 청크와 오브젝트를 옮기고, 청크 전체를 맵 크기로 삼는다.
+        let (mut width, mut height) = (map.width, map.height);
+        let mut offset = (0., 0.);
+        if let Some((left, top, right, bottom)) = chunk_bounds(&layers) {
+            layers.iter_mut().for_each(|layer| offset_chunks(layer, -left, -top));
+            width = (right - left) as u32;
+            height = (bottom - top) as u32;
+            offset = (-left as f32 * map.tile_width as f32, -top as f32 * map.tile_height as f32);
+        }
+
         // layer의 이름이 collision인 일반 Tile의 경우에는 해당하는 값의 좌표를 blocks에 넣는다.
         let mut blocks = vec![];
-
-        for (_, layer) in layers.iter().enumerate() {
-            if let tiled::LayerData::Finite(tiles) = &layer.tiles {
-                if layer.name == "collision" {
+        let block = |x: i32, y: i32| {
+            Rect::new(
+                x * map.tile_width as i32,
+                y * map.tile_height as i32,
+                map.tile_width,
+                map.tile_height,
+            )
+        };
+
+        for layer in layers.iter().filter(|layer| layer.name == "collision") {
+            match &layer.tiles {
+                tiled::LayerData::Finite(tiles) => {
                     for y in 0..map.height {
                         for x in 0..map.width {
-                            let gid = tiles[y as usize][x as usize].gid;
+                            let tile = &tiles[y as usize][x as usize];
+                            let gid = tile.gid;
                             if gid != 0 {
-                                blocks.push(Rect::new(
-                                    (x * map.tile_width) as i32,
-                                    (y * map.tile_height) as i32,
-                                    map.tile_width,
-                                    map.tile_height,
-                                ));
+                                blocks.push(block(x as i32, y as i32));
+                            }
+                        }
+                    }
+                }
+                tiled::LayerData::Infinite(chunks) => {
+                    for chunk in chunks.values() {
+                        for (y, row) in chunk.tiles.iter().enumerate() {
+                            for (x, tile) in row.iter().enumerate() {
+                                if tile.gid != 0 {
+                                    blocks.push(block(chunk.x + x as i32, chunk.y + y as i32));
+                                }
                             }
                         }
                     }
@@ -179,77 +306,120 @@ impl<'a> Level<'a> {
                 let objects = &object_group.objects;
                 for object in objects {
                     if let tiled::ObjectShape::Polyline { points } = &object.shape {
-                        let mut from = Vector2(
-                            (object.x + points[0].0).ceil(),
-                            (object.y + points[0].1).ceil(),
-                        );
+                        let (x, y) = (object.x + offset.0, object.y + offset.1);
+                        let mut from = Vector2((x + points[0].0).ceil(), (y + points[0].1).ceil());
 
                         points.iter().skip(1).for_each(|&point| {
-                            let to =
-                                Vector2((object.x + point.0).ceil(), (object.y + point.1).ceil());
-                            slopes.push(Slope { from, to });
+                            let to = Vector2((x + point.0).ceil(), (y + point.1).ceil());
+                            slopes.push(Slope { from, to, ceiling: false });
                             from = to;
                         });
                     }
                 }
             } else if object_group.name == "start" {
                 let objects = &object_group.objects;
-                start_pos = Vector2(objects[0].x, objects[0].y);
+                start_pos = Vector2(objects[0].x + offset.0, objects[0].y + offset.1);
             } else if object_group.name == "doors" {
                 let objects = &object_group.objects;
                 for object in objects {
                     if let Some(PropertyValue::StringValue(s)) =
                         object.properties.get("destination")
                     {
+                        let (x, y) = (object.x + offset.0, object.y + offset.1);
+                        let entrance = match object.properties.get("entrance") {
+                            Some(PropertyValue::StringValue(entrance)) => Some(entrance.clone()),
+                            _ => None,
+                        };
                         doors.push(Door::new(
                             (*s).clone(),
+                            entrance,
                             Rectangle {
-                                left: object.x,
-                                right: object.x + object.width,
-                                top: object.y,
-                                bottom: object.y + object.height,
+                                left: x,
+                                right: x + object.width,
+                                top: y,
+                                bottom: y + object.height,
                                 width: object.width,
                                 height: object.height,
                             },
                         ));
                     }
                 }
+            } else if object_group.name == "entrances" {
+                // 입구 이름은 오브젝트 이름이고, facing 속성(left/right)으로 바라볼 방향을 정한다.
+                for object in &object_group.objects {
+                    let facing = match object.properties.get("facing") {
+                        Some(PropertyValue::StringValue(facing)) if facing == "left" => {
+                            Some(Direction::Left)
+                        }
+                        Some(PropertyValue::StringValue(facing)) if facing == "right" => {
+                            Some(Direction::Right)
+                        }
+                        _ => None,
+                    };
+                    entrances.push(Entrance {
+                        name: object.name.clone(),
+                        position: Vector2(object.x + offset.0, object.y + offset.1),
+                        facing,
+                    });
+                }
+            } else if object_group.name == "events" {
+                // event 속성(int)이 이벤트 번호이고, touch 속성이 true이면 닿기만 해도 시작된다.
+                for object in &object_group.objects {
+                    if let Some(PropertyValue::IntValue(event)) = object.properties.get("event") {
+                        let (x, y) = (object.x + offset.0, object.y + offset.1);
+                        let on_touch = matches!(
+                            object.properties.get("touch"),
+                            Some(PropertyValue::BoolValue(true))
+                        );
+                        triggers.push(EventTrigger {
+                            event: *event as u16,
+                            position: Rectangle {
+                                left: x,
+                                right: x + object.width,
+                                top: y,
+                                bottom: y + object.height,
+                                width: object.width,
+                                height: object.height,
+                            },
+                            on_touch,
+                        });
+                    }
+                }
             } else if object_group.name == "enemies" {
                 let objects = &object_group.objects;
                 for object in objects {
-                    if object.name == "bat" {
-                        let mut bat = Bat::new(object.x as i32, object.y as i32);
-                        bat.add_animation(
-                            "fly_left".into(),
-                            Rect::new(32, 32, 16, 16),
-                            150,
-                            false,
-                            3,
-                            1,
-                        );
-                        bat.add_animation(
-                            "fly_right".into(),
-                            Rect::new(32, 48, 16, 16),
-                            150,
-                            false,
-                            3,
-                            1,
-                        );
-                        bat.set_animation("fly_left".into());
-                        enemies.push(Box::new(bat));
+                    let object = tiled::Object {
+                        x: object.x + offset.0,
+                        y: object.y + offset.1,
+                        ..object.clone()
+                    };
+                    if let Some(enemy) =
+                        spawn_object(&enemy_registry, &object, &npc_table, animation_sets)
+                    {
+                        enemies.push(enemy);
                     }
                 }
             }
         }
 
-        Level {
+        // 맵 속성 stage는 쓸 스크립트 이름, event는 들어왔을 때 시작할 이벤트 번호이다.
+        let stage = match map.properties.get("stage") {
+            Some(PropertyValue::StringValue(stage)) => Some(stage.clone()),
+            _ => None,
+        };
+        let entry_event = match map.properties.get("event") {
+            Some(PropertyValue::IntValue(event)) => Some(*event as u16),
+            _ => None,
+        };
+
+        Ok(Level {
             x: 0,
             y: 0,
             cam_x: 0,
             cam_y: 0,
             tile_atlases,
-            width: map.width,
-            height: map.height,
+            width,
+            height,
             tile_width: map.tile_width,
             tile_height: map.tile_height,
             tile_widths,
@@ -259,10 +429,166 @@ impl<'a> Level<'a> {
             blocks,
             gids,
             slopes,
+            attributes: vec![],
             animations,
             start_pos,
             doors,
+            entrances,
+            triggers,
+            stage,
+            entry_event,
             enemies,
+            npc_table,
+            enemy_registry,
+        })
+    }
+
+    /// 원본 스테이지(.pxm)와 그에 맞는 타일셋(Prt*.pbm, *.pxa)으로 지도를 만든다.
+    /// stage는 `Stage/Cave.pxm`의 `Cave`, tileset은 `Stage/PrtCave.pbm`의 `Cave`에 해당한다.
+    /// .pxm이나 타일셋 이미지를 읽을 수 없으면 에러를 돌려준다. .pxa, .pxe는 없어도 된다.
+    pub fn from_pxm(
+        renderer: &mut dyn Renderer,
+        stage: &str,
+        tileset: &str,
+        animation_sets: &AnimationSets,
+    ) -> Result<Level> {
+        let stage_dir = ASSET_DIR.to_owned() + "Stage/";
+        let pxm_path = format!("{}{}.pxm", stage_dir, stage);
+        let pxm = Pxm::from_file(Path::new(&pxm_path))
+            .map_err(|e| Error::new(e.kind(), format!("{}: {}", pxm_path, e)))?;
+        let pxa =
+            Pxa::from_file(Path::new(&format!("{}{}.pxa", stage_dir, tileset))).unwrap_or_default();
+
+        let texture = format!("Stage/Prt{}.pbm", tileset);
+        let texture_size = renderer
+            .load_texture(&texture, Path::new(&(ASSET_DIR.to_owned() + &texture)))
+            .map_err(|e| Error::new(ErrorKind::NotFound, e))?;
+
+        // PXM의 타일 번호 0번은 gid 1번이 되도록 first_gid를 1로 둔다.
+        let tile_atlas = tile::TileAtlas::new(texture_size, 1, PXM_TILE_SIZE, PXM_TILE_SIZE);
+
+        let mut gids = HashMap::new();
+        gids.insert(0, 0);
+        for (j, _) in tile_atlas.atlas.iter().enumerate() {
+            gids.insert(j as u32 + 1, 0);
+        }
+
+        // 타일셋에 없는 번호는 빈 칸으로 그린다. 타일 속성은 그대로 쓴다.
+        let tile_count = tile_atlas.atlas.len();
+        let missing = pxm.count_missing_tiles(tile_count);
+        if missing > 0 {
+            eprintln!(
+                "warning: {} has {} tiles missing from {} ({} tiles)",
+                pxm_path, missing, texture, tile_count
+            );
+        }
+
+        let mut attributes = vec![];
+        let mut tiles = vec![];
+        for y in 0..pxm.height {
+            let mut row = vec![];
+            for x in 0..pxm.width {
+                let tile = pxm.get_tile(x, y);
+                row.push(tiled::LayerTile {
+                    gid: if (tile as usize) < tile_count { tile as u32 + 1 } else { 0 },
+                    flip_h: false,
+                    flip_v: false,
+                    flip_d: false,
+                });
+
+                attributes.push(pxa.get_attribute(tile));
+            }
+            tiles.push(row);
+        }
+
+        let layer = tiled::Layer {
+            name: "background".into(),
+            opacity: 1.0,
+            visible: true,
+            offset_x: 0.,
+            offset_y: 0.,
+            tiles: tiled::LayerData::Finite(tiles),
+            properties: HashMap::new(),
+            layer_index: 0,
+        };
+
+        let mut textures = HashMap::new();
+        textures.insert(0, texture);
+        let mut tile_atlases = HashMap::new();
+        tile_atlases.insert(0, tile_atlas);
+        let mut tile_widths = HashMap::new();
+        tile_widths.insert(0, PXM_TILE_SIZE);
+        let mut tile_heights = HashMap::new();
+        tile_heights.insert(0, PXM_TILE_SIZE);
+
+        let mut level = Level {
+            x: 0,
+            y: 0,
+            cam_x: 0,
+            cam_y: 0,
+            tile_atlases,
+            width: pxm.width,
+            height: pxm.height,
+            tile_width: PXM_TILE_SIZE,
+            tile_height: PXM_TILE_SIZE,
+            tile_widths,
+            tile_heights,
+            layers: vec![layer],
+            textures,
+            blocks: vec![],
+            gids,
+            slopes: vec![],
+            attributes,
+            animations: HashMap::new(),
+            start_pos: Vector2(0., 0.),
+            doors: vec![],
+            entrances: vec![],
+            triggers: vec![],
+            stage: Some(stage.into()),
+            entry_event: None,
+            enemies: vec![],
+            npc_table: load_npc_table(),
+            enemy_registry: EnemyRegistry::default(),
+        };
+
+        // 같은 이름의 .pxe가 있다면 스테이지의 엔티티를 배치한다.
+        if let Ok(pxe) = Pxe::from_file(Path::new(&format!("{}{}.pxe", stage_dir, stage))) {
+            level.spawn_entities(&pxe.entities, animation_sets);
+        }
+
+        Ok(level)
+    }
+
+    /// PXE 엔티티를 등록된 적으로 만들어 enemies에 추가하고, 이벤트가 달린 엔티티는 triggers에 추가한다.
+    /// 아직 플래그 상태가 없으므로 모든 플래그가 꺼진 것으로 본다. 0번 NPC는 빈 자리이지만 이벤트는 가질 수 있다.
+    pub fn spawn_entities(&mut self, entities: &[PxeEntity], animation_sets: &AnimationSets) {
+        for entity in entities {
+            if !entity.should_spawn(false) {
+                continue;
+            }
+
+            let (x, y) = entity.get_position(self.tile_width, self.tile_height);
+            if entity.event != 0 && (entity.is_event_when_touched() || entity.is_interactable()) {
+                self.triggers.push(EventTrigger {
+                    event: entity.event,
+                    position: Rect::new(x, y, self.tile_width, self.tile_height).into(),
+                    on_touch: entity.is_event_when_touched(),
+                });
+            }
+
+            if entity.npc_type == 0 {
+                continue;
+            }
+
+            if let Some(enemy) = self.enemy_registry.spawn_by_npc_type(
+                entity.npc_type,
+                x,
+                y,
+                &self.npc_table,
+                animation_sets,
+            ) {
+                self.enemies.push(enemy);
+            }
         }
     }
 
@@ -283,6 +609,11 @@ impl<'a> Level<'a> {
         (tile_x, tile_y)
     }
 
+    /// 맵 전체의 픽셀 크기 (논리 좌표)
+    pub fn get_pixel_size(&self) -> (u32, u32) {
+        (self.width * self.tile_width, self.height * self.tile_height)
+    }
+
     /// translate (tile_x, tile_y)
     /// to coordinates
     pub fn get_tile_xy(&self, tile_x: u32, tile_y: u32) -> (f64, f64) {
@@ -302,10 +633,14 @@ impl<'a> Level<'a> {
         });
     }
 
-    pub fn render(&self, canvas: &mut WindowCanvas, camera_rect: &Rect) {
+    pub fn render(&self, renderer: &mut dyn Renderer, camera_rect: &Rect) {
         for (i, layer) in self.layers.iter().enumerate() {
-            if layer.name != "collision" {
-                if let tiled::LayerData::Finite(tiles) = &layer.tiles {
+            if layer.name == "collision" {
+                continue;
+            }
+
+            match &layer.tiles {
+                tiled::LayerData::Finite(tiles) => {
                     let (tile_left, tile_top) = self.point_to_tile(i, camera_rect.x, camera_rect.y);
                     let (tile_right, tile_bottom) = self.point_to_tile(
                         i,
@@ -323,55 +658,59 @@ impl<'a> Level<'a> {
                     let tile_start_x = camera_rect.x - tile_left * tile_width as i32;
                     let tile_start_y = camera_rect.y - tile_top * tile_height as i32;
 
+                    // 카메라가 타일 중간에 걸쳐 있으면 오른쪽/아래에 한 칸이 더 보인다.
+                    // 맵이 화면보다 작을 수도 있으므로 맵 크기를 넘지 않도록 한다.
+                    let tile_right = (tile_right + 1).min(self.width as i32);
+                    let tile_bottom = (tile_bottom + 1).min(self.height as i32);
+
                     for y in tile_top..tile_bottom {
                         for x in tile_left..tile_right {
-                            let gid = tiles[y as usize][x as usize].gid;
-
-                            if gid != 0 {
-                                // gid 로 부터 tile_atlases의 index를 구함
-                                // tile_atlases의 모든 first_gid 중 gid 값보다 큰 것 중에 가장 작은 인덱스를 구할 것
-                                // 해당 인덱스가 tile_atlases의 인덱스이다.
-                                // TODO : 이와 같은 방식은 비 경제적이다.
-                                // tile_atlas를 생성할 때, 어떤 texutre index인지, 그리고 해당 texture의 어떤 위치인지를
-                                // 등록하는 편이 좋다.
-                                // 즉 말하자면 Vector이면 되지, 굳이 HashMap일 필요가 없다.
-                                // Vec<(texture_idx: usize, x, y, w, h)> 이면 됨..
-                                let idx_gid = self.gids.get(&gid).unwrap();
-
-                                let tile_atlas = self.tile_atlases.get(idx_gid).unwrap();
-
-                                let rect = if layer.name == "animation" {
-                                    let animation = self
-                                        .animations
-                                        .get(&tiles[y as usize][x as usize].gid)
-                                        .unwrap();
-                                    tile_atlas.get_tile_rect(animation.get_current_frame())
-                                } else {
-                                    tile_atlas.get_tile_rect(gid)
-                                };
+                            let dest = Rect::new(
+                                (x - tile_left) * tile_width as i32 - tile_start_x,
+                                (y - tile_top) * tile_height as i32 - tile_start_y,
+                                tile_width,
+                                tile_height,
+                            );
+                            self.render_tile(renderer, layer, &tiles[y as usize][x as usize], dest);
+                        }
+                    }
+                }
+                tiled::LayerData::Infinite(chunks) => {
+                    let (tile_width, tile_height) =
+                        (self.tile_width as i32, self.tile_height as i32);
+
+                    for chunk in chunks.values() {
+                        let chunk_rect = Rect::new(
+                            chunk.x * tile_width,
+                            chunk.y * tile_height,
+                            chunk.width * self.tile_width,
+                            chunk.height * self.tile_height,
+                        );
+                        // 화면에 걸치지 않은 청크는 건너뛴다.
+                        if !collides_with(&chunk_rect, camera_rect) {
+                            continue;
+                        }
 
+                        // 청크 안에서 화면에 걸친 타일만 그린다.
+                        let left = ((camera_rect.left() - chunk_rect.left()) / tile_width).max(0);
+                        let top = ((camera_rect.top() - chunk_rect.top()) / tile_height).max(0);
+                        let right = ((camera_rect.right() - chunk_rect.left() + tile_width - 1)
+                            / tile_width)
+                            .min(chunk.width as i32);
+                        let bottom = ((camera_rect.bottom() - chunk_rect.top() + tile_height - 1)
+                            / tile_height)
+                            .min(chunk.height as i32);
+
+                        for y in top..bottom {
+                            for x in left..right {
                                 let dest = Rect::new(
-                                    (((x - tile_left) as i32 * tile_width as i32 - tile_start_x)
-                                        as f32
-                                        * SPRITE_SCALE) as i32,
-                                    (((y - tile_top) as i32 * tile_height as i32 - tile_start_y)
-                                        as f32
-                                        * SPRITE_SCALE) as i32,
-                                    (tile_width as f32 * SPRITE_SCALE) as u32,
-                                    (tile_height as f32 * SPRITE_SCALE) as u32,
+                                    chunk_rect.left() + x * tile_width - camera_rect.left(),
+                                    chunk_rect.top() + y * tile_height - camera_rect.top(),
+                                    self.tile_width,
+                                    self.tile_height,
                                 );
-
-                                canvas
-                                    .copy_ex(
-                                        &self.textures[idx_gid],
-                                        Some(rect),
-                                        Some(dest),
-                                        0.0,
-                                        None,
-                                        false,
-                                        false,
-                                    )
-                                    .unwrap();
+                                let tile = &chunk.tiles[y as usize][x as usize];
+                                self.render_tile(renderer, layer, tile, dest);
                             }
                         }
                     }
@@ -380,30 +719,172 @@ impl<'a> Level<'a> {
         }
     }
 
-    pub fn render_enemies(&self, graphics: &Graphics, canvas: &mut WindowCanvas) {
+    /// layer의 타일 하나를 화면의 dest에 그린다. 빈 타일(gid 0)은 그리지 않는다.
+    fn render_tile(
+        &self,
+        renderer: &mut dyn Renderer,
+        layer: &tiled::Layer,
+        tile: &tiled::LayerTile,
+        dest: Rect,
+    ) {
+        let gid = tile.gid;
+        if gid == 0 {
+            return;
+        }
+
+        // gid 로 부터 tile_atlases의 index를 구함
+        // tile_atlases의 모든 first_gid 중 gid 값보다 큰 것 중에 가장 작은 인덱스를 구할 것
+        // 해당 인덱스가 tile_atlases의 인덱스이다.
+        // TODO : 이와 같은 방식은 비 경제적이다.
+        // tile_atlas를 생성할 때, 어떤 texutre index인지, 그리고 해당 texture의 어떤 위치인지를
+        // 등록하는 편이 좋다.
+        // 즉 말하자면 Vector이면 되지, 굳이 HashMap일 필요가 없다.
+        // Vec<(texture_idx: usize, x, y, w, h)> 이면 됨..
+        // 타일셋에 없는 gid는 그리지 않는다.
+        let Some(idx_gid) = self.gids.get(&gid) else {
+            return;
+        };
+
+        let tile_atlas = self.tile_atlases.get(idx_gid).unwrap();
+
+        let rect = if layer.name == "animation" {
+            let animation = self.animations.get(&gid).unwrap();
+            tile_atlas.get_tile_rect(animation.get_current_frame())
+        } else {
+            tile_atlas.get_tile_rect(gid)
+        };
+
+        // Tiled에서 뒤집거나 돌려 놓은 타일
+        let transform = Transform::from_tiled(tile.flip_h, tile.flip_v, tile.flip_d);
+        renderer.copy(&self.textures[idx_gid], rect, dest, transform);
+    }
+
+    pub fn render_enemies(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32) {
         // render enemies
         for enemy in &self.enemies {
             let renderable = enemy.to_renderable().unwrap();
-            graphics.render_sprite(canvas, renderable);
+            renderable.render(renderer, camera, alpha);
         }
     }
 
+    /// (tile_x, tile_y) 칸의 타일 속성
+    pub fn get_attribute(&self, tile_x: u32, tile_y: u32) -> TileAttribute {
+        if tile_x >= self.width || tile_y >= self.height {
+            return TileAttribute::Background;
+        }
+
+        self.attributes
+            .get((tile_y * self.width + tile_x) as usize)
+            .copied()
+            .unwrap_or(TileAttribute::Background)
+    }
+
+    /// other와 겹치는 칸들의 영역과 타일 속성
+    pub fn collided_attributes(&self, other: &Rect) -> Vec<(Rect, TileAttribute)> {
+        let mut collided = vec![];
+        if self.attributes.is_empty() || other.width() == 0 || other.height() == 0 {
+            return collided;
+        }
+
+        let tile_width = self.tile_width as i32;
+        let tile_height = self.tile_height as i32;
+
+        let left = other.left().div_euclid(tile_width).max(0);
+        let top = other.top().div_euclid(tile_height).max(0);
+        let right = (other.right() - 1).div_euclid(tile_width).min(self.width as i32 - 1);
+        let bottom = (other.bottom() - 1).div_euclid(tile_height).min(self.height as i32 - 1);
+
+        for y in top..=bottom {
+            for x in left..=right {
+                let rect =
+                    Rect::new(x * tile_width, y * tile_height, self.tile_width, self.tile_height);
+                collided.push((rect, self.get_attribute(x as u32, y as u32)));
+            }
+        }
+
+        collided
+    }
+
+    /// 플레이어가 통과할 수 없는 블럭들
     pub fn collided_blocks(&self, other: &Rect) -> Vec<Rect> {
-        self.blocks.iter().filter(|block| collides_with(*block, other)).copied().collect()
+        self.collided_solid_blocks(other, TileAttribute::is_player_solid)
+    }
+
+    /// 적이나 아이템이 통과할 수 없는 블럭들
+    pub fn collided_npc_blocks(&self, other: &Rect) -> Vec<Rect> {
+        self.collided_solid_blocks(other, TileAttribute::is_npc_solid)
+    }
+
+    fn collided_solid_blocks(
+        &self,
+        other: &Rect,
+        is_solid: impl Fn(&TileAttribute) -> bool,
+    ) -> Vec<Rect> {
+        let mut blocks: Vec<Rect> =
+            self.blocks.iter().filter(|block| collides_with(*block, other)).copied().collect();
+
+        blocks.extend(
+            self.collided_attributes(other)
+                .into_iter()
+                .filter(|(_, attribute)| is_solid(attribute))
+                .map(|(rect, _)| rect),
+        );
+
+        blocks
     }
 
     pub fn collided_slopes(&self, other: &Rect) -> Vec<Slope> {
-        self.slopes
+        let mut slopes: Vec<Slope> = self
+            .slopes
             .iter()
             .filter(|slope| (*slope).collides_with((*other).into()))
             .copied()
-            .collect()
+            .collect();
+
+        slopes.extend(self.collided_attributes(other).into_iter().filter_map(
+            |(rect, attribute)| match attribute {
+                TileAttribute::Slope(kind) => Some(Slope::from_attribute(kind, rect)),
+                _ => None,
+            },
+        ));
+
+        slopes
+    }
+
+    pub fn get_entrance(&self, name: &str) -> Option<&Entrance> {
+        self.entrances.iter().find(|entrance| entrance.name == name)
     }
 
     pub fn collided_doors(&self, other: &Rect) -> Vec<Door> {
         self.doors.iter().filter(|door| collides_with(&door.position, other)).cloned().collect()
     }
 
+    /// other에 닿아 시작될 이벤트. interacting이면 살펴봐야 시작되는 이벤트도 찾는다.
+    pub fn triggered_event(&self, other: &Rect, interacting: bool) -> Option<u16> {
+        self.triggers
+            .iter()
+            .find(|trigger| {
+                (trigger.on_touch || interacting) && collides_with(&trigger.position, other)
+            })
+            .map(|trigger| trigger.event)
+    }
+
+    /// 체력이 다한 적들을 enemies에서 빼서 반환한다.
+    pub fn remove_dead_enemies(&mut self) -> Vec<Box<dyn EnemyRenderable>> {
+        let (dead, alive) =
+            std::mem::take(&mut self.enemies).into_iter().partition(|enemy| enemy.is_dead());
+        self.enemies = alive;
+        dead
+    }
+
+    /// other와 겹치는 첫 번째 적
+    pub fn collided_enemy_mut(&mut self, other: &Rect) -> Option<&mut dyn Enemy> {
+        self.enemies
+            .iter_mut()
+            .find(|enemy| collides_with(&enemy.get_collision(), other))
+            .and_then(|enemy| enemy.to_enemy_mut())
+    }
+
     pub fn collided_enemies(&self, other: &Rect) -> Vec<&Box<dyn EnemyRenderable>> {
         self.enemies
             .iter()
@@ -412,3 +893,33 @@ impl<'a> Level<'a> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{DrawCommand, RecordingRenderer};
+
+    #[test]
+    fn pxm_tiles_missing_from_tileset_are_empty() {
+        // e_Labo.pxm은 32개뿐인 PrtLabo.pbm에 없는 37번 타일을 쓴다.
+        let mut renderer = RecordingRenderer::new();
+        let level =
+            Level::from_pxm(&mut renderer, "e_Labo", "Labo", &AnimationSets::default()).unwrap();
+        let tile_count = level.tile_atlases[&0].atlas.len();
+        let pxm = Pxm::from_file(Path::new(&(ASSET_DIR.to_owned() + "Stage/e_Labo.pxm"))).unwrap();
+        assert!(pxm.count_missing_tiles(tile_count) > 0);
+
+        let tiled::LayerData::Finite(tiles) = &level.layers[0].tiles else {
+            panic!("PXM layer must be finite");
+        };
+        assert!(tiles.iter().flatten().all(|tile| tile.gid as usize <= tile_count));
+
+        let (width, height) = level.get_pixel_size();
+        level.render(&mut renderer, &Rect::new(0, 0, width, height));
+        assert!(renderer.commands.iter().all(|command| match command {
+            DrawCommand::Copy { src, .. } =>
+                src.bottom() as u32 <= renderer.textures[&level.textures[&0]].1,
+            _ => true,
+        }));
+    }
+}
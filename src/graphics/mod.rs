@@ -1,26 +1,40 @@
 pub mod animate_sprite;
 pub mod animated_tile;
 pub mod animation;
+pub mod animation_set;
+pub mod camera;
+pub mod dialogue_box;
 pub mod door;
+pub mod effect;
+pub mod font;
 pub mod graphics;
 pub mod hud;
 pub mod level;
+pub mod pxa;
+pub mod pxe;
+pub mod pxm;
+pub mod renderer;
 pub mod sprite;
+pub mod stage_table;
 pub mod texture_manager;
 pub mod tile;
 
 pub use animate_sprite::*;
 pub use animated_tile::*;
 pub use animation::*;
+pub use animation_set::*;
+pub use camera::*;
+pub use dialogue_box::*;
 pub use door::*;
+pub use effect::*;
+pub use font::*;
 pub use graphics::*;
 pub use hud::*;
+pub use renderer::*;
 
-use sdl2::{
-    rect::Rect,
-    render::{Texture, WindowCanvas},
-};
+use sdl2::rect::Rect;
 pub use sprite::*;
+pub use stage_table::*;
 pub use texture_manager::*;
 
 use crate::physics::{Sided, Sides};
@@ -106,5 +120,7 @@ impl Rectangle {
 
 pub trait Renderable {
     fn get_name(&self) -> String;
-    fn render(&self, canvas: &mut WindowCanvas, texture: &Texture);
+    /// camera는 월드 좌표를 화면 좌표로 옮길 때 쓴다. 화면에 고정된 것은 무시한다.
+    /// alpha는 직전 틱과 현재 틱 사이의 보간 비율이다.
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32);
 }
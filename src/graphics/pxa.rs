@@ -0,0 +1,177 @@
+use std::fs;
+use std::io::Result;
+use std::path::Path;
+
+use crate::player::Direction;
+
+/// 기울어진 타일의 종류
+/// 원본의 0x50 ~ 0x57 (물 속은 0x70 ~ 0x77) 순서를 그대로 따른다.
+/// 각 종류의 높이는 타일 위쪽에서부터 잰 (왼쪽 끝, 오른쪽 끝) 값이다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlopeKind {
+    /// 천장, 16 -> 8
+    CeilingA,
+    /// 천장, 8 -> 0
+    CeilingB,
+    /// 천장, 0 -> 8
+    CeilingC,
+    /// 천장, 8 -> 16
+    CeilingD,
+    /// 바닥, 0 -> 8
+    FloorA,
+    /// 바닥, 8 -> 16
+    FloorB,
+    /// 바닥, 16 -> 8
+    FloorC,
+    /// 바닥, 8 -> 0
+    FloorD,
+}
+
+impl SlopeKind {
+    pub fn from_index(index: u8) -> SlopeKind {
+        match index & 0x07 {
+            0 => SlopeKind::CeilingA,
+            1 => SlopeKind::CeilingB,
+            2 => SlopeKind::CeilingC,
+            3 => SlopeKind::CeilingD,
+            4 => SlopeKind::FloorA,
+            5 => SlopeKind::FloorB,
+            6 => SlopeKind::FloorC,
+            _ => SlopeKind::FloorD,
+        }
+    }
+
+    pub fn is_ceiling(&self) -> bool {
+        matches!(
+            self,
+            SlopeKind::CeilingA | SlopeKind::CeilingB | SlopeKind::CeilingC | SlopeKind::CeilingD
+        )
+    }
+
+    /// 타일 높이를 2로 나눈 단위로 (왼쪽, 오른쪽) 높이를 반환
+    pub fn half_heights(&self) -> (u32, u32) {
+        match self {
+            SlopeKind::CeilingA => (2, 1),
+            SlopeKind::CeilingB => (1, 0),
+            SlopeKind::CeilingC => (0, 1),
+            SlopeKind::CeilingD => (1, 2),
+            SlopeKind::FloorA => (0, 1),
+            SlopeKind::FloorB => (1, 2),
+            SlopeKind::FloorC => (2, 1),
+            SlopeKind::FloorD => (1, 0),
+        }
+    }
+}
+
+/// PXA 한 바이트를 해석한 타일 속성
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileAttribute {
+    Background,
+    Foreground,
+    Solid,
+    PlayerSolid,
+    NpcSolid,
+    Spikes,
+    Slope(SlopeKind),
+    Water,
+    Wind(Direction),
+    Current(Direction),
+}
+
+impl From<u8> for TileAttribute {
+    fn from(raw: u8) -> TileAttribute {
+        match raw {
+            0x02 | 0x60 => TileAttribute::Water,
+            0x03 | 0x44 => TileAttribute::NpcSolid,
+            0x05 | 0x41 | 0x43 | 0x61 => TileAttribute::Solid,
+            0x46 => TileAttribute::PlayerSolid,
+            0x42 | 0x62 => TileAttribute::Spikes,
+            0x50..=0x57 | 0x70..=0x77 => TileAttribute::Slope(SlopeKind::from_index(raw)),
+            0x80..=0x83 => TileAttribute::Wind(wind_direction(raw)),
+            0xA0..=0xA3 => TileAttribute::Current(wind_direction(raw)),
+            _ if raw & 0x40 != 0 => TileAttribute::Foreground,
+            _ => TileAttribute::Background,
+        }
+    }
+}
+
+fn wind_direction(raw: u8) -> Direction {
+    match raw & 0x03 {
+        0 => Direction::Left,
+        1 => Direction::Up,
+        2 => Direction::Right,
+        _ => Direction::Down,
+    }
+}
+
+impl TileAttribute {
+    /// 플레이어가 통과할 수 없는 타일인가
+    pub fn is_player_solid(&self) -> bool {
+        matches!(self, TileAttribute::Solid | TileAttribute::PlayerSolid)
+    }
+
+    /// NPC가 통과할 수 없는 타일인가
+    pub fn is_npc_solid(&self) -> bool {
+        matches!(self, TileAttribute::Solid | TileAttribute::NpcSolid)
+    }
+}
+
+/// 타일셋별 속성 테이블 (.pxa)
+/// 타일 번호 하나당 한 바이트의 속성을 가진다.
+#[derive(Debug, Clone, Default)]
+pub struct Pxa {
+    pub attributes: Vec<u8>,
+}
+
+impl Pxa {
+    pub fn from_file(path: &Path) -> Result<Pxa> {
+        Ok(Pxa { attributes: fs::read(path)? })
+    }
+
+    /// 타일 번호에 해당하는 속성. 테이블 밖의 타일은 배경으로 취급한다.
+    pub fn get_attribute(&self, tile: u8) -> TileAttribute {
+        self.attributes
+            .get(tile as usize)
+            .map(|raw| (*raw).into())
+            .unwrap_or(TileAttribute::Background)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_bytes_to_attributes() {
+        assert_eq!(TileAttribute::from(0x00), TileAttribute::Background);
+        assert_eq!(TileAttribute::from(0x40), TileAttribute::Foreground);
+        assert_eq!(TileAttribute::from(0x41), TileAttribute::Solid);
+        assert_eq!(TileAttribute::from(0x61), TileAttribute::Solid);
+        assert_eq!(TileAttribute::from(0x46), TileAttribute::PlayerSolid);
+        assert_eq!(TileAttribute::from(0x44), TileAttribute::NpcSolid);
+        assert_eq!(TileAttribute::from(0x42), TileAttribute::Spikes);
+        assert_eq!(TileAttribute::from(0x02), TileAttribute::Water);
+        assert_eq!(TileAttribute::from(0x50), TileAttribute::Slope(SlopeKind::CeilingA));
+        assert_eq!(TileAttribute::from(0x77), TileAttribute::Slope(SlopeKind::FloorD));
+        assert_eq!(TileAttribute::from(0x80), TileAttribute::Wind(Direction::Left));
+        assert_eq!(TileAttribute::from(0xA3), TileAttribute::Current(Direction::Down));
+    }
+
+    #[test]
+    fn player_and_npc_solids_differ() {
+        let solid = TileAttribute::from(0x41);
+        let player_only = TileAttribute::from(0x46);
+        let npc_only = TileAttribute::from(0x44);
+
+        assert!(solid.is_player_solid() && solid.is_npc_solid());
+        assert!(player_only.is_player_solid() && !player_only.is_npc_solid());
+        assert!(!npc_only.is_player_solid() && npc_only.is_npc_solid());
+    }
+
+    #[test]
+    fn tiles_outside_table_are_background() {
+        let pxa = Pxa { attributes: vec![0x00, 0x41] };
+        assert_eq!(pxa.get_attribute(1), TileAttribute::Solid);
+        assert_eq!(pxa.get_attribute(2), TileAttribute::Background);
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+/// PXE 파일의 매직 넘버
+pub const PXE_MAGIC: &[u8; 4] = b"PXE\0";
+
+/// 엔티티 레코드 한 개의 크기 (x, y, flag, event, type, bits 각 u16)
+const PXE_RECORD_SIZE: usize = 12;
+
+/// 플레이어가 닿으면 event가 시작된다.
+pub const BIT_EVENT_WHEN_TOUCHED: u16 = 0x0100;
+/// 이 플래그가 켜져 있을 때만 나타난다.
+pub const BIT_APPEAR_WHEN_FLAG_SET: u16 = 0x0800;
+/// 반대 방향(오른쪽)을 보며 나타난다.
+pub const BIT_SPAWN_IN_OTHER_DIRECTION: u16 = 0x1000;
+/// 플레이어가 아래 키로 살펴보면 event가 시작된다.
+pub const BIT_INTERACTABLE: u16 = 0x2000;
+/// 이 플래그가 켜져 있으면 나타나지 않는다.
+pub const BIT_HIDE_WHEN_FLAG_SET: u16 = 0x4000;
+
+/// 스테이지 엔티티 하나 (.pxe 레코드)
+/// x, y는 타일 단위 좌표이다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PxeEntity {
+    pub x: u16,
+    pub y: u16,
+    pub flag_id: u16,
+    pub event: u16,
+    pub npc_type: u16,
+    pub bits: u16,
+}
+
+impl PxeEntity {
+    /// 타일 크기를 곱한 픽셀 좌표
+    pub fn get_position(&self, tile_width: u32, tile_height: u32) -> (i32, i32) {
+        ((self.x as u32 * tile_width) as i32, (self.y as u32 * tile_height) as i32)
+    }
+
+    pub fn is_facing_right(&self) -> bool {
+        self.bits & BIT_SPAWN_IN_OTHER_DIRECTION != 0
+    }
+
+    pub fn is_event_when_touched(&self) -> bool {
+        self.bits & BIT_EVENT_WHEN_TOUCHED != 0
+    }
+
+    pub fn is_interactable(&self) -> bool {
+        self.bits & BIT_INTERACTABLE != 0
+    }
+
+    /// flag_id의 상태에 따라 이 엔티티가 나타나야 하는지
+    pub fn should_spawn(&self, flag_set: bool) -> bool {
+        if self.bits & BIT_APPEAR_WHEN_FLAG_SET != 0 && !flag_set {
+            return false;
+        }
+
+        !(self.bits & BIT_HIDE_WHEN_FLAG_SET != 0 && flag_set)
+    }
+}
+
+/// 스테이지 엔티티 목록 (.pxe)
+#[derive(Debug, Clone, Default)]
+pub struct Pxe {
+    pub entities: Vec<PxeEntity>,
+}
+
+impl Pxe {
+    pub fn from_file(path: &Path) -> Result<Pxe> {
+        Pxe::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Pxe> {
+        if bytes.len() < 8 || &bytes[0..4] != PXE_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a PXE file"));
+        }
+
+        let count = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let body = &bytes[8..];
+
+        if body.len() < count * PXE_RECORD_SIZE {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "PXE entity data is truncated"));
+        }
+
+        let entities = body
+            .chunks_exact(PXE_RECORD_SIZE)
+            .take(count)
+            .map(|record| {
+                let field = |i: usize| u16::from_le_bytes([record[i * 2], record[i * 2 + 1]]);
+                PxeEntity {
+                    x: field(0),
+                    y: field(1),
+                    flag_id: field(2),
+                    event: field(3),
+                    npc_type: field(4),
+                    bits: field(5),
+                }
+            })
+            .collect();
+
+        Ok(Pxe { entities })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pxe_bytes(records: &[[u16; 6]]) -> Vec<u8> {
+        let mut bytes = PXE_MAGIC.to_vec();
+        bytes.extend_from_slice(&(records.len() as u32).to_le_bytes());
+        for field in records.iter().flatten() {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_entity_records() {
+        let pxe =
+            Pxe::from_bytes(&pxe_bytes(&[[3, 4, 100, 200, 46, BIT_EVENT_WHEN_TOUCHED], [0; 6]]))
+                .unwrap();
+
+        assert_eq!(pxe.entities.len(), 2);
+        let entity = pxe.entities[0];
+        assert_eq!(
+            entity,
+            PxeEntity { x: 3, y: 4, flag_id: 100, event: 200, npc_type: 46, bits: 0x0100 }
+        );
+        assert!(entity.is_event_when_touched());
+        assert!(!entity.is_interactable());
+        assert_eq!(entity.get_position(16, 16), (48, 64));
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(Pxe::from_bytes(b"PXM\0\0\0\0\0").unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut truncated = pxe_bytes(&[[1; 6]]);
+        truncated.pop();
+        assert_eq!(Pxe::from_bytes(&truncated).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn spawn_follows_flag_bits() {
+        let entity = |bits| PxeEntity { x: 0, y: 0, flag_id: 1, event: 0, npc_type: 0, bits };
+
+        assert!(entity(0).should_spawn(true));
+        assert!(!entity(BIT_APPEAR_WHEN_FLAG_SET).should_spawn(false));
+        assert!(entity(BIT_APPEAR_WHEN_FLAG_SET).should_spawn(true));
+        assert!(entity(BIT_HIDE_WHEN_FLAG_SET).should_spawn(false));
+        assert!(!entity(BIT_HIDE_WHEN_FLAG_SET).should_spawn(true));
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+/// PXM 파일의 매직 넘버
+pub const PXM_MAGIC: &[u8; 4] = b"PXM\x10";
+
+/// Cave Story 원본 스테이지 맵 (.pxm)
+/// 헤더 다음에 가로 * 세로 만큼의 타일 번호가 한 바이트씩 들어있다.
+#[derive(Debug, Clone)]
+pub struct Pxm {
+    pub width: u32,
+    pub height: u32,
+    pub tiles: Vec<u8>,
+}
+
+impl Pxm {
+    pub fn from_file(path: &Path) -> Result<Pxm> {
+        Pxm::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Pxm> {
+        if bytes.len() < 8 || &bytes[0..4] != PXM_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a PXM file"));
+        }
+
+        let width = u16::from_le_bytes([bytes[4], bytes[5]]) as u32;
+        let height = u16::from_le_bytes([bytes[6], bytes[7]]) as u32;
+        let size = (width * height) as usize;
+
+        if bytes.len() < 8 + size {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "PXM tile data is truncated"));
+        }
+
+        Ok(Pxm { width, height, tiles: bytes[8..8 + size].to_vec() })
+    }
+
+    /// (x, y) 위치의 타일 번호
+    pub fn get_tile(&self, x: u32, y: u32) -> u8 {
+        self.tiles[(y * self.width + x) as usize]
+    }
+
+    /// tile_count 개의 타일만 있는 타일셋에 없는 번호를 쓰는 칸 수
+    /// 원본 데이터에도 타일셋보다 큰 번호를 쓰는 스테이지가 있다. (e_Labo)
+    pub fn count_missing_tiles(&self, tile_count: usize) -> usize {
+        self.tiles.iter().filter(|&&tile| tile as usize >= tile_count).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pxm_bytes(width: u16, height: u16, tiles: &[u8]) -> Vec<u8> {
+        let mut bytes = PXM_MAGIC.to_vec();
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(tiles);
+        bytes
+    }
+
+    #[test]
+    fn reads_tiles_row_by_row() {
+        let pxm = Pxm::from_bytes(&pxm_bytes(3, 2, &[0, 1, 2, 3, 4, 5])).unwrap();
+
+        assert_eq!((pxm.width, pxm.height), (3, 2));
+        assert_eq!(pxm.get_tile(2, 0), 2);
+        assert_eq!(pxm.get_tile(0, 1), 3);
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(Pxm::from_bytes(b"PXE\x10\0\0\0\0").unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            Pxm::from_bytes(&pxm_bytes(2, 2, &[0, 1, 2])).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn counts_tiles_missing_from_tileset() {
+        let pxm = Pxm::from_bytes(&pxm_bytes(2, 2, &[0, 31, 32, 37])).unwrap();
+
+        assert_eq!(pxm.count_missing_tiles(32), 2);
+        assert_eq!(pxm.count_missing_tiles(256), 0);
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use sdl2::{pixels::Color, rect::Rect};
+
+use super::wrap_text;
+
+/// 내장 8x8 글꼴의 글자 폭
+pub const GLYPH_WIDTH: i32 = 8;
+
+/// 텍스처를 그릴 때의 뒤집기와 회전
+/// 뒤집기를 먼저 하고, dest 중심을 기준으로 시계 방향으로 angle도 돌린다.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Transform {
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    pub angle: f64,
+}
+
+impl Transform {
+    pub fn flip(flip_horizontal: bool, flip_vertical: bool) -> Self {
+        Transform { flip_horizontal, flip_vertical, angle: 0.0 }
+    }
+
+    pub fn rotate(angle: f64) -> Self {
+        Transform { angle, ..Default::default() }
+    }
+
+    /// Tiled 타일 gid의 뒤집기 비트
+    /// 대각선 뒤집기(x, y 바꾸기)는 세로로 뒤집은 뒤 90도 돌린 것과 같다.
+    /// 가로/세로 뒤집기는 대각선 뒤집기 다음에 적용되므로 90도 돌린 뒤에는 서로 바뀐다.
+    pub fn from_tiled(flip_h: bool, flip_v: bool, flip_d: bool) -> Self {
+        if flip_d {
+            Transform { flip_horizontal: flip_v, flip_vertical: !flip_h, angle: 90.0 }
+        } else {
+            Transform::flip(flip_h, flip_v)
+        }
+    }
+}
+
+/// 그리기 백엔드
+/// 좌표는 모두 논리 좌표(320x240)이며, 화면 크기로 확대하는 것은 구현이 맡는다.
+/// 텍스처는 load_texture로 등록한 이름으로 가리킨다.
+pub trait Renderer {
+    /// path의 이미지를 name으로 등록하고 (폭, 높이)를 반환한다.
+    fn load_texture(&mut self, name: &str, path: &Path) -> Result<(u32, u32), String>;
+
+    /// 등록된 텍스처의 (폭, 높이)
+    fn texture_size(&self, name: &str) -> Option<(u32, u32)>;
+
+    /// name 텍스처의 src 영역을 transform 해서 dest에 그린다. 등록되지 않은 텍스처는 무시한다.
+    fn copy(&mut self, name: &str, src: Rect, dest: Rect, transform: Transform);
+
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+
+    fn set_clip_rect(&mut self, rect: Option<Rect>);
+
+    /// (x, y)에 한 줄을 그리고 그린 폭을 반환한다.
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, size: u16, color: Color) -> u32;
+
+    /// 글자 줄의 폭
+    fn measure_text(&self, text: &str, size: u16) -> u32;
+
+    /// max_width에 맞춰 줄을 나누어 (x, y)부터 size 간격으로 그리고 그린 높이를 반환한다.
+    fn draw_text_wrapped(
+        &mut self,
+        text: &str,
+        position: (i32, i32),
+        max_width: u32,
+        size: u16,
+        color: Color,
+    ) -> u32 {
+        draw_wrapped_lines(self, text, position, max_width, size, color)
+    }
+
+    fn clear(&mut self);
+
+    fn present(&mut self);
+}
+
+/// measure_text로 줄을 나누고 draw_text로 한 줄씩 size 간격으로 그린다.
+/// Renderer::draw_text_wrapped의 기본 구현이다.
+pub fn draw_wrapped_lines<R: Renderer + ?Sized>(
+    renderer: &mut R,
+    text: &str,
+    position: (i32, i32),
+    max_width: u32,
+    size: u16,
+    color: Color,
+) -> u32 {
+    let lines = wrap_text(text, max_width, |line| renderer.measure_text(line, size));
+    for (i, line) in lines.iter().enumerate() {
+        renderer.draw_text(line, position.0, position.1 + i as i32 * size as i32, size, color);
+    }
+    lines.len() as u32 * size as u32
+}
+
+/// RecordingRenderer가 기록하는 그리기 명령
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    Copy { name: String, src: Rect, dest: Rect, transform: Transform },
+    FillRect { rect: Rect, color: Color },
+    SetClipRect(Option<Rect>),
+    Text { text: String, x: i32, y: i32, size: u16, color: Color },
+    Clear,
+    Present,
+}
+
+/// 창 없이 그리기 명령만 기록하는 백엔드
+/// 이미지는 디코딩하지 않고 PNG/BMP 헤더에서 크기만 읽는다.
+#[derive(Debug, Default)]
+pub struct RecordingRenderer {
+    pub textures: HashMap<String, (u32, u32)>,
+    /// 마지막 present 이후의 명령
+    pub commands: Vec<DrawCommand>,
+    /// present 된 횟수
+    pub frames: u32,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 파일 없이 텍스처를 등록한다.
+    pub fn insert_texture(&mut self, name: &str, width: u32, height: u32) {
+        self.textures.insert(name.into(), (width, height));
+    }
+
+    /// 그려진 텍스처 복사 명령들
+    pub fn copies(&self, name: &str) -> Vec<&DrawCommand> {
+        self.commands
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::Copy { name: n, .. } if n == name))
+            .collect()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn load_texture(&mut self, name: &str, path: &Path) -> Result<(u32, u32), String> {
+        let size = read_image_size(path)?;
+        self.textures.insert(name.into(), size);
+        Ok(size)
+    }
+
+    fn texture_size(&self, name: &str) -> Option<(u32, u32)> {
+        self.textures.get(name).copied()
+    }
+
+    fn copy(&mut self, name: &str, src: Rect, dest: Rect, transform: Transform) {
+        if self.textures.contains_key(name) {
+            self.commands.push(DrawCommand::Copy { name: name.into(), src, dest, transform });
+        }
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.commands.push(DrawCommand::FillRect { rect, color });
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<Rect>) {
+        self.commands.push(DrawCommand::SetClipRect(rect));
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, size: u16, color: Color) -> u32 {
+        self.commands.push(DrawCommand::Text { text: text.into(), x, y, size, color });
+        self.measure_text(text, size)
+    }
+
+    fn measure_text(&self, text: &str, _size: u16) -> u32 {
+        text.chars().count() as u32 * GLYPH_WIDTH as u32
+    }
+
+    fn clear(&mut self) {
+        self.commands.clear();
+        self.commands.push(DrawCommand::Clear);
+    }
+
+    fn present(&mut self) {
+        self.commands.push(DrawCommand::Present);
+        self.frames += 1;
+    }
+}
+
+/// PNG, BMP(.pbm) 헤더에서 이미지의 (폭, 높이)를 읽는다.
+pub fn read_image_size(path: &Path) -> Result<(u32, u32), String> {
+    let mut header = [0u8; 26];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let be =
+        |i: usize| u32::from_be_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+    let le =
+        |i: usize| i32::from_le_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+
+    if header.starts_with(b"\x89PNG") {
+        Ok((be(16), be(20)))
+    } else if header.starts_with(b"BM") {
+        // 높이가 음수면 위에서 아래로 저장된 이미지이다.
+        Ok((le(18).unsigned_abs(), le(22).unsigned_abs()))
+    } else {
+        Err(format!("{}: unknown image format", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_wrapped_text_line_by_line() {
+        let mut renderer = RecordingRenderer::new();
+        let height = renderer.draw_text_wrapped("hello cave story", (4, 10), 80, 8, Color::WHITE);
+
+        assert_eq!(height, 16);
+        assert_eq!(
+            renderer.commands,
+            [
+                DrawCommand::Text {
+                    text: "hello cave".into(),
+                    x: 4,
+                    y: 10,
+                    size: 8,
+                    color: Color::WHITE
+                },
+                DrawCommand::Text {
+                    text: "story".into(),
+                    x: 4,
+                    y: 18,
+                    size: 8,
+                    color: Color::WHITE
+                },
+            ]
+        );
+    }
+}
@@ -1,9 +1,6 @@
-use sdl2::{
-    rect::Rect,
-    render::{Texture, WindowCanvas},
-};
+use sdl2::rect::Rect;
 
-use crate::game::SPRITE_SCALE;
+use super::{Renderer, Transform};
 
 #[derive(Debug, Clone)]
 pub struct Sprite {
@@ -21,17 +18,21 @@ impl Sprite {
         self.name.clone()
     }
 
-    pub fn render(&self, x: i32, y: i32, canvas: &mut WindowCanvas, texture: &Texture) {
-        let dest = Rect::new(
-            (x as f32 * SPRITE_SCALE) as i32,
-            (y as f32 * SPRITE_SCALE) as i32,
-            (self.source_rect.width() as f32 * SPRITE_SCALE) as u32,
-            (self.source_rect.height() as f32 * SPRITE_SCALE) as u32,
-        );
-
-        canvas
-            .copy_ex(texture, Some(self.source_rect), Some(dest), 0.0, None, false, false)
-            .unwrap();
+    pub fn render(&self, x: i32, y: i32, renderer: &mut dyn Renderer) {
+        self.render_transformed(x, y, renderer, Transform::default());
+    }
+
+    /// 뒤집거나 돌려서 그린다. 좌우가 대칭인 그림은 한 방향만 시트에 있으면 된다.
+    pub fn render_transformed(
+        &self,
+        x: i32,
+        y: i32,
+        renderer: &mut dyn Renderer,
+        transform: Transform,
+    ) {
+        let dest = Rect::new(x, y, self.source_rect.width(), self.source_rect.height());
+
+        renderer.copy(&self.name, self.source_rect, dest, transform);
     }
 
     pub fn set_source_rect(&mut self, rect: Rect) {
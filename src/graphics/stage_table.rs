@@ -0,0 +1,79 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use crate::constant::ASSET_DIR;
+
+/// 스테이지 표 파일. ASSET_DIR 아래에 있다.
+pub const STAGES_FILE: &str = "stages.cfg";
+
+/// 원본 스테이지 하나
+/// file은 `Stage/<file>.pxm`, tileset은 `Stage/Prt<tileset>.pbm`에 해당한다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageInfo {
+    /// <TRA가 쓰는 스테이지 번호
+    pub number: u16,
+    pub tileset: String,
+    pub file: String,
+    pub name: String,
+}
+
+/// 원본 게임의 스테이지 표
+/// 파일은 한 줄에 `번호 = 타일셋, 파일 이름, 스테이지 이름`을 쓴다. `#`으로 시작하는 줄은 무시한다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageTable {
+    pub stages: Vec<StageInfo>,
+}
+
+impl StageTable {
+    /// ASSET_DIR의 STAGES_FILE을 읽는다.
+    pub fn load() -> Result<StageTable> {
+        StageTable::from_file(Path::new(&(ASSET_DIR.to_owned() + STAGES_FILE)))
+    }
+
+    pub fn from_file(path: &Path) -> Result<StageTable> {
+        StageTable::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<StageTable> {
+        let mut stages: Vec<StageInfo> = vec![];
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || Error::new(ErrorKind::InvalidData, format!("invalid stage: {}", line));
+            let (number, value) = line.split_once('=').ok_or_else(invalid)?;
+            let number = number.trim().parse::<u16>().map_err(|_| invalid())?;
+            let fields: Vec<&str> = value.splitn(3, ',').map(str::trim).collect();
+            if fields.len() != 3 || fields[0].is_empty() || fields[1].is_empty() {
+                return Err(invalid());
+            }
+            if stages.iter().any(|stage| stage.number == number) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("duplicated stage: {}", number),
+                ));
+            }
+
+            stages.push(StageInfo {
+                number,
+                tileset: fields[0].into(),
+                file: fields[1].into(),
+                name: fields[2].into(),
+            });
+        }
+
+        Ok(StageTable { stages })
+    }
+
+    pub fn get(&self, number: u16) -> Option<&StageInfo> {
+        self.stages.iter().find(|stage| stage.number == number)
+    }
+
+    /// 파일 이름(`Cave`)으로 찾는다.
+    pub fn get_by_file(&self, file: &str) -> Option<&StageInfo> {
+        self.stages.iter().find(|stage| stage.file == file)
+    }
+}
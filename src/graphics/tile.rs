@@ -1,6 +1,4 @@
 use sdl2::rect::Rect;
-use sdl2::render::Texture;
-use sdl2::render::TextureQuery;
 
 /// TileAtlas
 /// struct tiles
@@ -21,12 +19,13 @@ pub struct TileAtlas {
 }
 
 impl TileAtlas {
-    pub fn new(texture: &Texture, first_gid: u32, w: u32, h: u32) -> TileAtlas {
-        let query: TextureQuery = texture.query();
+    /// texture_size는 타일셋 텍스처의 (폭, 높이)
+    pub fn new(texture_size: (u32, u32), first_gid: u32, w: u32, h: u32) -> TileAtlas {
+        let (texture_width, texture_height) = texture_size;
 
         let mut atlas: Vec<(u32, u32, u32, u32)> = vec![];
-        let x_size: u32 = query.width / w;
-        let y_size: u32 = query.height / h;
+        let x_size: u32 = texture_width / w;
+        let y_size: u32 = texture_height / h;
 
         let mut u_acc: u32;
         let mut v_acc: u32 = 0;
@@ -41,17 +40,10 @@ impl TileAtlas {
             v_acc = next_v_acc;
         }
 
-        let hor_length: u32 = query.width / w;
-        let ver_length: u32 = query.height / h;
+        let hor_length: u32 = texture_width / w;
+        let ver_length: u32 = texture_height / h;
 
-        TileAtlas {
-            first_gid,
-            texture_width: query.width,
-            texture_height: query.height,
-            hor_length,
-            ver_length,
-            atlas,
-        }
+        TileAtlas { first_gid, texture_width, texture_height, hor_length, ver_length, atlas }
     }
 
     /// return rect of tile
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use sdl2::keyboard::Scancode;
+
+/// 게임에서 쓰는 조작. 게임 코드는 키 대신 Action으로 입력을 확인한다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    LookUp,
+    LookDown,
+    Jump,
+    Shoot,
+    NextWeapon,
+    PreviousWeapon,
+    Inventory,
+    Map,
+    Pause,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::LookUp,
+        Action::LookDown,
+        Action::Jump,
+        Action::Shoot,
+        Action::NextWeapon,
+        Action::PreviousWeapon,
+        Action::Inventory,
+        Action::Map,
+        Action::Pause,
+        Action::Quit,
+    ];
+
+    pub fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| action.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// 조작별로 묶인 키들
+/// 설정 파일은 한 줄에 `Action = Key, Key` 형식이며 `#`으로 시작하는 줄은 무시한다.
+/// 키 이름은 SDL의 스캔코드 이름(예: Left, Z, Left Shift)을 쓴다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionMap {
+    bindings: HashMap<Action, Vec<Scancode>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        let mut map = ActionMap::empty();
+        map.bind(Action::MoveLeft, Scancode::Left);
+        map.bind(Action::MoveRight, Scancode::Right);
+        map.bind(Action::LookUp, Scancode::Up);
+        map.bind(Action::LookDown, Scancode::Down);
+        map.bind(Action::Jump, Scancode::Z);
+        map.bind(Action::Shoot, Scancode::X);
+        map.bind(Action::NextWeapon, Scancode::S);
+        map.bind(Action::PreviousWeapon, Scancode::A);
+        map.bind(Action::Inventory, Scancode::Q);
+        map.bind(Action::Map, Scancode::W);
+        map.bind(Action::Pause, Scancode::P);
+        map.bind(Action::Quit, Scancode::Escape);
+        map
+    }
+}
+
+impl ActionMap {
+    /// 아무 키도 묶이지 않은 상태
+    pub fn empty() -> Self {
+        ActionMap { bindings: HashMap::new() }
+    }
+
+    /// action에 key를 추가로 묶는다.
+    pub fn bind(&mut self, action: Action, key: Scancode) {
+        let keys = self.bindings.entry(action).or_default();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    pub fn unbind(&mut self, action: Action, key: Scancode) {
+        if let Some(keys) = self.bindings.get_mut(&action) {
+            keys.retain(|&bound| bound != key);
+        }
+    }
+
+    /// action에 묶인 키를 모두 푼다.
+    pub fn clear(&mut self, action: Action) {
+        self.bindings.remove(&action);
+    }
+
+    pub fn get_keys(&self, action: Action) -> &[Scancode] {
+        self.bindings.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    /// key가 묶인 조작들
+    pub fn get_actions(&self, key: Scancode) -> Vec<Action> {
+        Action::ALL.iter().copied().filter(|&action| self.get_keys(action).contains(&key)).collect()
+    }
+
+    /// 설정 파일을 읽는다. 파일에 없는 조작은 기본 키를 쓴다.
+    pub fn from_file(path: &Path) -> Result<ActionMap> {
+        ActionMap::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<ActionMap> {
+        let mut map = ActionMap::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid =
+                || Error::new(ErrorKind::InvalidData, format!("invalid binding: {}", line));
+            let (action, keys) = line.split_once('=').ok_or_else(invalid)?;
+            let action = Action::from_name(action).ok_or_else(invalid)?;
+
+            map.clear(action);
+            for key in keys.split(',').filter(|key| !key.trim().is_empty()) {
+                map.bind(action, Scancode::from_name(key.trim()).ok_or_else(invalid)?);
+            }
+        }
+
+        Ok(map)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# Action = Key, Key\n");
+        for action in Action::ALL {
+            let keys: Vec<&str> = self.get_keys(action).iter().map(|key| key.name()).collect();
+            text.push_str(&format!("{} = {}\n", action.name(), keys.join(", ")));
+        }
+        text
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trips() {
+        let mut map = ActionMap::default();
+        map.bind(Action::Jump, Scancode::Space);
+        map.clear(Action::Map);
+
+        assert_eq!(ActionMap::parse(&map.to_text()).unwrap(), map);
+    }
+
+    #[test]
+    fn missing_actions_keep_default_keys() {
+        let map = ActionMap::parse("# comment\n\n jump = Space, Left Shift \nshoot =\n").unwrap();
+
+        assert_eq!(map.get_keys(Action::Jump), [Scancode::Space, Scancode::LShift]);
+        assert!(map.get_keys(Action::Shoot).is_empty());
+        assert_eq!(map.get_keys(Action::MoveLeft), [Scancode::Left]);
+    }
+
+    #[test]
+    fn rejects_invalid_bindings() {
+        for text in ["Jump Space", "Fly = Space", "Jump = NotAKey"] {
+            assert_eq!(ActionMap::parse(text).unwrap_err().kind(), ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, HashSet};
+
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::GameControllerSubsystem;
+
+use super::Action;
+
+/// 스틱이 이 값보다 적게 기울면 무시한다. (-32768 ~ 32767)
+pub const AXIS_DEADZONE: i16 = 8000;
+
+/// 패드 버튼/스틱과 조작의 연결
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControllerMap {
+    buttons: HashMap<Button, Action>,
+    /// 스틱 축을 (음수 방향, 양수 방향) 조작에 연결한다.
+    axes: HashMap<Axis, (Action, Action)>,
+}
+
+impl Default for ControllerMap {
+    fn default() -> Self {
+        let mut map = ControllerMap { buttons: HashMap::new(), axes: HashMap::new() };
+        map.bind_button(Button::DPadLeft, Action::MoveLeft);
+        map.bind_button(Button::DPadRight, Action::MoveRight);
+        map.bind_button(Button::DPadUp, Action::LookUp);
+        map.bind_button(Button::DPadDown, Action::LookDown);
+        map.bind_button(Button::A, Action::Jump);
+        map.bind_button(Button::B, Action::Shoot);
+        map.bind_button(Button::X, Action::Shoot);
+        map.bind_button(Button::LeftShoulder, Action::PreviousWeapon);
+        map.bind_button(Button::RightShoulder, Action::NextWeapon);
+        map.bind_button(Button::Y, Action::Inventory);
+        map.bind_button(Button::Back, Action::Map);
+        map.bind_button(Button::Start, Action::Pause);
+        map.bind_axis(Axis::LeftX, Action::MoveLeft, Action::MoveRight);
+        map.bind_axis(Axis::LeftY, Action::LookUp, Action::LookDown);
+        map
+    }
+}
+
+impl ControllerMap {
+    pub fn bind_button(&mut self, button: Button, action: Action) {
+        self.buttons.insert(button, action);
+    }
+
+    pub fn bind_axis(&mut self, axis: Axis, negative: Action, positive: Action) {
+        self.axes.insert(axis, (negative, positive));
+    }
+
+    pub fn get_button_action(&self, button: Button) -> Option<Action> {
+        self.buttons.get(&button).copied()
+    }
+
+    pub fn get_axis_actions(&self, axis: Axis) -> Option<(Action, Action)> {
+        self.axes.get(&axis).copied()
+    }
+}
+
+/// 패드 하나의 눌린 버튼과 스틱 값
+#[derive(Debug, Clone, Default)]
+pub struct PadState {
+    pub buttons: HashSet<Button>,
+    pub axes: HashMap<Axis, i16>,
+}
+
+impl PadState {
+    /// 이 패드가 action을 누르고 있는가
+    pub fn holds(&self, map: &ControllerMap, action: Action) -> bool {
+        self.buttons.iter().any(|&button| map.get_button_action(button) == Some(action))
+            || self.axes.iter().any(|(&axis, &value)| match map.get_axis_actions(axis) {
+                Some((negative, positive)) => {
+                    (value < -AXIS_DEADZONE && negative == action)
+                        || (value > AXIS_DEADZONE && positive == action)
+                }
+                None => false,
+            })
+    }
+}
+
+/// 열려 있는 게임 패드들
+/// 패드는 열려 있는 동안만 이벤트를 보내므로 연결이 끊길 때까지 들고 있어야 한다.
+/// SDL은 시작할 때 이미 연결된 패드에 대해서도 ControllerDeviceAdded를 보낸다.
+pub struct Controllers {
+    subsystem: GameControllerSubsystem,
+    opened: HashMap<u32, GameController>,
+}
+
+impl Controllers {
+    pub fn new(subsystem: GameControllerSubsystem) -> Self {
+        Controllers { subsystem, opened: HashMap::new() }
+    }
+
+    /// ControllerDeviceAdded의 joystick_index 패드를 열고 instance id를 반환한다.
+    pub fn add(&mut self, joystick_index: u32) -> Option<u32> {
+        if !self.subsystem.is_game_controller(joystick_index) {
+            return None;
+        }
+
+        let controller = self.subsystem.open(joystick_index).ok()?;
+        let id = controller.instance_id();
+        self.opened.insert(id, controller);
+        Some(id)
+    }
+
+    /// ControllerDeviceRemoved의 instance id 패드를 닫는다.
+    pub fn remove(&mut self, id: u32) {
+        self.opened.remove(&id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.opened.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty()
+    }
+}
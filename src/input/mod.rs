@@ -1,25 +1,53 @@
+pub mod action;
+pub mod controller;
+pub mod replay;
+
+pub use action::*;
+pub use controller::*;
+pub use replay::*;
+
+use sdl2::controller::{Axis, Button};
 use sdl2::keyboard::Scancode;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Default)]
 pub struct Input {
     held_keys: HashMap<Scancode, bool>,
     pressed_keys: HashMap<Scancode, bool>,
     release_keys: HashMap<Scancode, bool>,
+    /// 키와 조작의 연결
+    pub bindings: ActionMap,
+    /// 패드 버튼/스틱과 조작의 연결
+    pub controller_bindings: ControllerMap,
+    /// instance id 별 패드 상태
+    pads: HashMap<u32, PadState>,
+    held_actions: HashSet<Action>,
+    pressed_actions: HashSet<Action>,
+    released_actions: HashSet<Action>,
 }
 
 impl Input {
+    pub fn new(bindings: ActionMap) -> Self {
+        Input { bindings, ..Default::default() }
+    }
+
     /// This function gets called at the beginning of each new frame
     /// to reset the keys that are no longer relevant
     pub fn begin_new_frame(&mut self) {
         self.pressed_keys.clear();
         self.release_keys.clear();
+        self.pressed_actions.clear();
+        self.released_actions.clear();
     }
 
     pub fn key_up_event(&mut self, scancode: &Option<Scancode>) {
         if let Some(s) = scancode {
             self.release_keys.insert(*s, true);
             self.held_keys.insert(*s, false);
+
+            for action in self.bindings.get_actions(*s) {
+                self.refresh_action(action);
+            }
         }
     }
 
@@ -28,6 +56,69 @@ impl Input {
         if let Some(s) = scancode {
             self.pressed_keys.insert(*s, true);
             self.held_keys.insert(*s, true);
+
+            for action in self.bindings.get_actions(*s) {
+                self.press_action(action);
+            }
+        }
+    }
+
+    pub fn button_down_event(&mut self, which: u32, button: Button) {
+        self.pads.entry(which).or_default().buttons.insert(button);
+        if let Some(action) = self.controller_bindings.get_button_action(button) {
+            self.refresh_action(action);
+        }
+    }
+
+    pub fn button_up_event(&mut self, which: u32, button: Button) {
+        self.pads.entry(which).or_default().buttons.remove(&button);
+        if let Some(action) = self.controller_bindings.get_button_action(button) {
+            self.refresh_action(action);
+        }
+    }
+
+    /// 스틱이 AXIS_DEADZONE 을 넘어 기울면 해당 방향의 조작을 누른 것으로 본다.
+    pub fn axis_event(&mut self, which: u32, axis: Axis, value: i16) {
+        self.pads.entry(which).or_default().axes.insert(axis, value);
+        if let Some((negative, positive)) = self.controller_bindings.get_axis_actions(axis) {
+            self.refresh_action(negative);
+            self.refresh_action(positive);
+        }
+    }
+
+    /// 패드가 빠지면 그 패드로 누르고 있던 조작을 모두 뗀다.
+    pub fn controller_removed(&mut self, which: u32) {
+        self.pads.remove(&which);
+        for action in Action::ALL {
+            self.refresh_action(action);
+        }
+    }
+
+    /// 키보드나 패드 중 하나라도 action을 누르고 있는가
+    fn is_action_source_held(&self, action: Action) -> bool {
+        self.bindings.get_keys(action).iter().any(|&key| self.is_key_held(key))
+            || self.pads.values().any(|pad| pad.holds(&self.controller_bindings, action))
+    }
+
+    /// 입력 장치들의 상태에 맞춰 action을 누르거나 뗀다.
+    /// 같은 조작에 묶인 다른 키나 버튼이 아직 눌려 있으면 떼지 않은 것으로 본다.
+    fn refresh_action(&mut self, action: Action) {
+        if self.is_action_source_held(action) {
+            self.press_action(action);
+        } else {
+            self.release_action(action);
+        }
+    }
+
+    fn press_action(&mut self, action: Action) {
+        if self.held_actions.insert(action) {
+            self.pressed_actions.insert(action);
+        }
+    }
+
+    fn release_action(&mut self, action: Action) {
+        if self.held_actions.remove(&action) {
+            self.released_actions.insert(action);
         }
     }
 
@@ -45,4 +136,40 @@ impl Input {
     pub fn is_key_held(&self, key: Scancode) -> bool {
         *self.held_keys.get(&key).unwrap_or(&false)
     }
+
+    /// 이번 프레임에 조작이 시작되었는가
+    pub fn was_pressed(&self, action: Action) -> bool {
+        self.pressed_actions.contains(&action)
+    }
+
+    /// 이번 프레임에 조작이 끝났는가
+    pub fn was_released(&self, action: Action) -> bool {
+        self.released_actions.contains(&action)
+    }
+
+    pub fn is_held(&self, action: Action) -> bool {
+        self.held_actions.contains(&action)
+    }
+
+    /// 현재 프레임의 조작 상태를 저장한다.
+    pub fn snapshot(&self) -> InputSnapshot {
+        let actions = |set: &HashSet<Action>| {
+            let mut actions: Vec<Action> = set.iter().copied().collect();
+            actions.sort();
+            actions
+        };
+
+        InputSnapshot {
+            held: actions(&self.held_actions),
+            pressed: actions(&self.pressed_actions),
+            released: actions(&self.released_actions),
+        }
+    }
+
+    /// 저장된 조작 상태로 바꾼다.
+    pub fn restore(&mut self, snapshot: &InputSnapshot) {
+        self.held_actions = snapshot.held.iter().copied().collect();
+        self.pressed_actions = snapshot.pressed.iter().copied().collect();
+        self.released_actions = snapshot.released.iter().copied().collect();
+    }
 }
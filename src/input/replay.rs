@@ -0,0 +1,127 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use super::{Action, Input};
+
+/// 한 틱 동안의 조작 상태
+/// 키가 아닌 조작을 기록하므로 키 설정이나 입력 장치가 달라도 같은 결과가 나온다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputSnapshot {
+    pub held: Vec<Action>,
+    pub pressed: Vec<Action>,
+    pub released: Vec<Action>,
+}
+
+impl InputSnapshot {
+    /// `held;pressed;released` 형식의 한 줄. 각 항목은 조작 이름을 `,`로 잇는다.
+    fn to_line(&self) -> String {
+        let actions =
+            |actions: &[Action]| actions.iter().map(Action::name).collect::<Vec<_>>().join(",");
+
+        format!("{};{};{}", actions(&self.held), actions(&self.pressed), actions(&self.released))
+    }
+
+    fn from_line(line: &str) -> Result<InputSnapshot> {
+        let actions = |text: &str| -> Result<Vec<Action>> {
+            text.split(',')
+                .filter(|action| !action.trim().is_empty())
+                .map(|action| {
+                    Action::from_name(action)
+                        .ok_or_else(|| invalid_data(format!("invalid action: {}", action)))
+                })
+                .collect()
+        };
+
+        let fields: Vec<&str> = line.split(';').collect();
+        if fields.len() != 3 {
+            return Err(invalid_data(format!("invalid input line: {}", line)));
+        }
+
+        Ok(InputSnapshot {
+            held: actions(fields[0])?,
+            pressed: actions(fields[1])?,
+            released: actions(fields[2])?,
+        })
+    }
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// 틱마다 입력을 기록한다.
+/// 파일은 첫 줄에 `seed <난수 시드>`, 그 뒤로 한 줄에 한 틱씩 InputSnapshot을 쓴다.
+#[derive(Debug, Clone, Default)]
+pub struct InputRecorder {
+    pub seed: u64,
+    pub frames: Vec<InputSnapshot>,
+}
+
+impl InputRecorder {
+    pub fn new(seed: u64) -> Self {
+        InputRecorder { seed, frames: vec![] }
+    }
+
+    pub fn record(&mut self, input: &Input) {
+        self.frames.push(input.snapshot());
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("seed {}\n", self.seed);
+        for frame in &self.frames {
+            text.push_str(&frame.to_line());
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+/// 기록된 입력을 틱마다 다시 넣어준다.
+#[derive(Debug, Clone, Default)]
+pub struct InputReplay {
+    pub seed: u64,
+    pub frames: Vec<InputSnapshot>,
+    position: usize,
+}
+
+impl InputReplay {
+    pub fn from_file(path: &Path) -> Result<InputReplay> {
+        InputReplay::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<InputReplay> {
+        let mut lines = text.lines();
+        let seed = lines
+            .next()
+            .and_then(|line| line.strip_prefix("seed "))
+            .and_then(|seed| seed.trim().parse().ok())
+            .ok_or_else(|| invalid_data("missing seed".into()))?;
+        let frames = lines.map(InputSnapshot::from_line).collect::<Result<_>>()?;
+
+        Ok(InputReplay { seed, frames, position: 0 })
+    }
+
+    /// 다음 틱의 입력으로 input을 바꾼다. 기록이 끝났으면 아무 키도 누르지 않은 상태가 되고 false를 반환한다.
+    pub fn next(&mut self, input: &mut Input) -> bool {
+        match self.frames.get(self.position) {
+            Some(frame) => {
+                input.restore(frame);
+                self.position += 1;
+                true
+            }
+            None => {
+                input.restore(&InputSnapshot::default());
+                false
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.frames.len()
+    }
+}
@@ -4,6 +4,8 @@ pub mod graphics;
 pub mod input;
 pub mod physics;
 pub mod player;
+pub mod script;
+pub mod sound;
 
 pub enum GameResult {
     None,
@@ -0,0 +1,86 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// 1픽셀을 나누는 단위 수. 원본과 같이 1/512 픽셀 단위로 위치와 속도를 계산한다.
+pub const SUBPIXELS_PER_PIXEL: i32 = 0x200;
+
+/// 1/512 픽셀 단위의 고정소수점 값
+/// 위치는 그대로, 속도는 1ms 동안 움직이는 양으로 쓴다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+
+    pub const fn from_pixels(pixels: i32) -> Fixed {
+        Fixed(pixels * SUBPIXELS_PER_PIXEL)
+    }
+
+    /// 1/512 보다 작은 값은 버린다.
+    pub const fn from_f32(pixels: f32) -> Fixed {
+        Fixed((pixels * SUBPIXELS_PER_PIXEL as f32) as i32)
+    }
+
+    /// 픽셀 단위로 내림한다. 음수도 왼쪽/위쪽 픽셀이 된다.
+    pub const fn to_pixels(self) -> i32 {
+        self.0.div_euclid(SUBPIXELS_PER_PIXEL)
+    }
+
+    pub const fn abs(self) -> Fixed {
+        Fixed(self.0.abs())
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / SUBPIXELS_PER_PIXEL as f32
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(pixels: i32) -> Self {
+        Fixed::from_pixels(pixels)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Fixed) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, other: Fixed) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+/// 속도 * 시간(ms) 처럼 정수배 할 때 쓴다.
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, scalar: i32) -> Fixed {
+        Fixed(self.0 * scalar)
+    }
+}
@@ -1,2 +1,5 @@
 pub mod collision;
+pub mod fixed;
+
 pub use collision::*;
+pub use fixed::*;
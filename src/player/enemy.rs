@@ -1,11 +1,22 @@
-use sdl2::{
-    rect::Rect,
-    render::{Texture, WindowCanvas},
-};
+use std::collections::HashMap;
 
-use crate::graphics::{AnimateSprite, Renderable};
+use sdl2::rect::Rect;
 
-use super::{Direction, Player};
+use crate::game::interpolate;
+use crate::graphics::{AnimateSprite, AnimationSets, Camera, Renderable, Renderer, Transform};
+use crate::physics::Fixed;
+
+use super::{Direction, NpcEntry, NpcTable, Player};
+
+/// 원본 NPC 번호 중 First Cave의 박쥐
+pub const NPC_TYPE_BAT: u16 = 65;
+
+/// 박쥐가 위아래로 나는 속도
+pub const BAT_SPEED: Fixed = Fixed::from_f32(0.08);
+/// 박쥐가 처음 위치에서 위아래로 움직이는 범위 (px)
+pub const BAT_FLIGHT_RANGE: i32 = 20;
+/// 맞은 뒤 떨리며 깜빡이는 시간 (ms)
+pub const HURT_DURATION: u32 = 200;
 
 pub trait Enemy {
     fn update(&mut self, dt: u32, player: &Player);
@@ -20,6 +31,19 @@ pub trait Enemy {
         vertical: usize,
     );
     fn get_collision(&self) -> Rect;
+    fn get_damage(&self) -> i32;
+    /// 총알 등에 맞아 체력이 줄어든다.
+    fn take_damage(&mut self, amount: i32);
+    fn get_health(&self) -> i32;
+    fn is_dead(&self) -> bool {
+        self.get_health() <= 0
+    }
+    /// 죽을 때 떨어뜨리는 경험치
+    fn get_experience(&self) -> i32;
+    fn get_hit_sound(&self) -> u8;
+    fn get_death_sound(&self) -> u8;
+    /// 죽을 때 나는 연기의 양 (npc.tbl)
+    fn get_smoke_size(&self) -> u8;
 }
 
 pub trait EnemyRenderable: Enemy + Renderable {
@@ -28,19 +52,112 @@ pub trait EnemyRenderable: Enemy + Renderable {
     fn to_enemy_mut(&mut self) -> Option<&mut dyn Enemy>;
 }
 
+/// 적을 만드는 함수. (x, y)는 스프라이트 좌상단 위치이고, 능력치는 npc_table에서 찾는다.
+pub type EnemyConstructor = fn(
+    x: i32,
+    y: i32,
+    npc_table: &NpcTable,
+    animations: &AnimationSets,
+) -> Box<dyn EnemyRenderable>;
+
+/// 적 종류별 생성 함수
+/// Tiled 오브젝트 이름이나 PXE의 NPC 번호로 찾는다. 새 적은 Default에 등록하면 된다.
+#[derive(Clone)]
+pub struct EnemyRegistry {
+    names: HashMap<String, EnemyConstructor>,
+    npc_types: HashMap<u16, EnemyConstructor>,
+}
+
+impl Default for EnemyRegistry {
+    fn default() -> Self {
+        let mut registry = EnemyRegistry::empty();
+        registry.register("bat", Some(NPC_TYPE_BAT), Bat::spawn);
+        registry
+    }
+}
+
+impl EnemyRegistry {
+    pub fn empty() -> Self {
+        EnemyRegistry { names: HashMap::new(), npc_types: HashMap::new() }
+    }
+
+    /// name(Tiled 오브젝트 이름)과 npc_type(원본 NPC 번호)으로 constructor를 등록한다.
+    pub fn register(&mut self, name: &str, npc_type: Option<u16>, constructor: EnemyConstructor) {
+        self.names.insert(name.to_lowercase(), constructor);
+        if let Some(npc_type) = npc_type {
+            self.npc_types.insert(npc_type, constructor);
+        }
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<EnemyConstructor> {
+        self.names.get(&name.to_lowercase()).copied()
+    }
+
+    pub fn get_by_npc_type(&self, npc_type: u16) -> Option<EnemyConstructor> {
+        self.npc_types.get(&npc_type).copied()
+    }
+
+    /// 등록되지 않은 이름이면 경고를 남기고 None을 반환한다.
+    pub fn spawn_by_name(
+        &self,
+        name: &str,
+        x: i32,
+        y: i32,
+        npc_table: &NpcTable,
+        animations: &AnimationSets,
+    ) -> Option<Box<dyn EnemyRenderable>> {
+        match self.get_by_name(name) {
+            Some(constructor) => Some(constructor(x, y, npc_table, animations)),
+            None => {
+                eprintln!("warning: unknown enemy \"{}\" at ({}, {})", name, x, y);
+                None
+            }
+        }
+    }
+
+    /// 등록되지 않은 NPC 번호면 경고를 남기고 None을 반환한다.
+    pub fn spawn_by_npc_type(
+        &self,
+        npc_type: u16,
+        x: i32,
+        y: i32,
+        npc_table: &NpcTable,
+        animations: &AnimationSets,
+    ) -> Option<Box<dyn EnemyRenderable>> {
+        match self.get_by_npc_type(npc_type) {
+            Some(constructor) => Some(constructor(x, y, npc_table, animations)),
+            None => {
+                eprintln!("warning: unknown npc type {} at ({}, {})", npc_type, x, y);
+                None
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Bat {
     id: uuid::Uuid,
     animation: AnimateSprite,
-    x: i32,
-    y: i32,
-    starting_x: i32,
-    starting_y: i32,
+    x: Fixed,
+    y: Fixed,
+    /// 직전 틱의 위치(픽셀). 렌더링 보간에 쓴다.
+    previous: (i32, i32),
+    starting_x: Fixed,
+    starting_y: Fixed,
     should_move_up: bool,
-    dx: f32,
-    dy: f32,
+    dx: Fixed,
+    dy: Fixed,
     facing: Direction,
     pub collision: Rect,
+    pub max_health: i32,
+    pub current_health: i32,
+    pub damage: i32,
+    pub experience: i32,
+    pub hit_sound: u8,
+    pub death_sound: u8,
+    pub smoke_size: u8,
+    /// 남은 피격 효과 시간 (ms)
+    hurt: u32,
 }
 
 impl Bat {
@@ -52,37 +169,90 @@ impl Bat {
         Self {
             id: uuid::Uuid::new_v4(),
             animation,
-            x,
-            y,
-            starting_x: x,
-            starting_y: y,
+            x: Fixed::from_pixels(x),
+            y: Fixed::from_pixels(y),
+            previous: (x, y),
+            starting_x: Fixed::from_pixels(x),
+            starting_y: Fixed::from_pixels(y),
             should_move_up: false,
-            dx: 0.,
-            dy: 0.,
+            dx: Fixed::ZERO,
+            dy: Fixed::ZERO,
             facing: Direction::Idle,
             collision: Rect::new(0, 0, 16, 16),
+            max_health: 1,
+            current_health: 1,
+            damage: 1,
+            experience: 0,
+            hit_sound: 0,
+            death_sound: 0,
+            smoke_size: 1,
+            hurt: 0,
+        }
+    }
+
+    /// npc.tbl 능력치와 bat 애니메이션 세트를 갖춘 박쥐
+    pub fn spawn(
+        x: i32,
+        y: i32,
+        npc_table: &NpcTable,
+        animations: &AnimationSets,
+    ) -> Box<dyn EnemyRenderable> {
+        let mut bat = Bat::new(x, y);
+        if let Some(entry) = npc_table.get(NPC_TYPE_BAT) {
+            bat.set_stats(entry);
+        }
+        bat.animation = animations.get_sprite("bat");
+        bat.set_animation("fly".into());
+        Box::new(bat)
+    }
+
+    /// 픽셀 단위 위치
+    pub fn get_position(&self) -> (i32, i32) {
+        (self.x.to_pixels(), self.y.to_pixels())
+    }
+
+    /// npc.tbl의 능력치를 적용한다.
+    /// collision의 x, y는 스프라이트 좌상단으로부터의 충돌 영역 위치가 된다.
+    pub fn set_stats(&mut self, entry: &NpcEntry) {
+        self.collision = entry.get_hitbox();
+        self.max_health = entry.life as i32;
+        self.current_health = entry.life as i32;
+        self.damage = entry.damage;
+        self.experience = entry.experience;
+        self.hit_sound = entry.hit_sound;
+        self.death_sound = entry.death_sound;
+        self.smoke_size = entry.smoke_size;
+    }
+
+    /// 맞은 직후에는 좌우로 떨리며 깜빡인다. 보이지 않는 순간에는 None
+    fn get_hurt_offset(&self) -> Option<i32> {
+        if self.hurt == 0 {
+            return Some(0);
+        }
+        if (self.hurt / 50).is_multiple_of(2) {
+            return None;
         }
+
+        Some(if (self.hurt / 25).is_multiple_of(2) { 1 } else { -1 })
     }
 }
 
 impl Enemy for Bat {
     fn update(&mut self, dt: u32, player: &Player) {
         // move bat
-        self.dy = if self.should_move_up { -0.08 } else { 0.08 };
-        self.x = ((self.x as f32) + self.dx * dt as f32) as i32;
-        self.y = ((self.y as f32) + self.dy * dt as f32) as i32;
+        self.previous = self.get_position();
+        self.dy = if self.should_move_up { -BAT_SPEED } else { BAT_SPEED };
+        self.x += self.dx * dt as i32;
+        self.y += self.dy * dt as i32;
 
-        if self.y > (self.starting_y + 20) || self.y < (self.starting_y - 20) {
+        let range = Fixed::from_pixels(BAT_FLIGHT_RANGE);
+        if self.y > self.starting_y + range || self.y < self.starting_y - range {
             self.should_move_up = !self.should_move_up;
         }
 
         self.facing = if player.x > self.x { Direction::Right } else { Direction::Left };
-        self.set_animation(if self.facing == Direction::Right {
-            "fly_right".into()
-        } else {
-            "fly_left".into()
-        });
         self.animation.update(dt);
+        self.hurt = self.hurt.saturating_sub(dt);
     }
 
     fn set_animation(&mut self, animation: String) {
@@ -102,67 +272,57 @@ impl Enemy for Bat {
     }
 
     fn get_collision(&self) -> Rect {
-        Rect::new(self.x, self.y, self.collision.width(), self.collision.height())
+        Rect::new(
+            self.x.to_pixels() + self.collision.x,
+            self.y.to_pixels() + self.collision.y,
+            self.collision.width(),
+            self.collision.height(),
+        )
     }
-}
 
-impl Enemy for Box<Bat> {
-    fn update(&mut self, dt: u32, player: &Player) {
-        // move bat
-        self.dy = if self.should_move_up { -0.08 } else { 0.08 };
-        self.x = ((self.x as f32) + self.dx * dt as f32) as i32;
-        self.y = ((self.y as f32) + self.dy * dt as f32) as i32;
-        if self.y > (self.starting_y + 20) || self.y < (self.starting_y - 20) {
-            self.should_move_up = !self.should_move_up;
-        }
+    fn get_damage(&self) -> i32 {
+        self.damage
+    }
 
-        self.facing = if player.x > self.x { Direction::Right } else { Direction::Left };
-        self.set_animation(if self.facing == Direction::Right {
-            "fly_right".into()
-        } else {
-            "fly_left".into()
-        });
-        self.animation.update(dt);
+    fn take_damage(&mut self, amount: i32) {
+        self.current_health = 0.max(self.current_health - amount);
+        self.hurt = HURT_DURATION;
     }
 
-    fn set_animation(&mut self, animation: String) {
-        self.animation.set_animation(animation);
+    fn get_health(&self) -> i32 {
+        self.current_health
     }
 
-    fn add_animation(
-        &mut self,
-        name: String,
-        rect: Rect,
-        duration: u32,
-        play_once: bool,
-        horizontal: usize,
-        vertical: usize,
-    ) {
-        self.animation.add_animation(name, rect, duration, play_once, horizontal, vertical);
+    fn get_experience(&self) -> i32 {
+        self.experience
     }
 
-    fn get_collision(&self) -> Rect {
-        Rect::new(self.x, self.y, self.collision.width(), self.collision.height())
+    fn get_hit_sound(&self) -> u8 {
+        self.hit_sound
     }
-}
 
-impl Renderable for Bat {
-    fn get_name(&self) -> String {
-        "enemy".into()
+    fn get_death_sound(&self) -> u8 {
+        self.death_sound
     }
 
-    fn render(&self, canvas: &mut WindowCanvas, texture: &Texture) {
-        self.animation.render(self.x, self.y, canvas, texture);
+    fn get_smoke_size(&self) -> u8 {
+        self.smoke_size
     }
 }
 
-impl Renderable for Box<Bat> {
+impl Renderable for Bat {
     fn get_name(&self) -> String {
         "enemy".into()
     }
 
-    fn render(&self, canvas: &mut WindowCanvas, texture: &Texture) {
-        self.animation.render(self.x, self.y, canvas, texture);
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32) {
+        if let Some(offset) = self.get_hurt_offset() {
+            let (x, y) = interpolate(self.previous, self.get_position(), alpha);
+            let (x, y) = camera.to_screen(x + offset, y);
+            // 스프라이트는 왼쪽을 보는 것만 있으므로 오른쪽을 볼 때는 뒤집어 그린다.
+            let transform = Transform::flip(self.facing == Direction::Right, false);
+            self.animation.render_transformed(x, y, renderer, transform);
+        }
     }
 }
 
@@ -1,5 +1,11 @@
 pub mod enemy;
+pub mod npc_table;
+pub mod pickup;
 pub mod player;
+pub mod weapon;
 
 pub use enemy::*;
+pub use npc_table::*;
+pub use pickup::*;
 pub use player::*;
+pub use weapon::*;
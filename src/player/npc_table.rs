@@ -0,0 +1,180 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use sdl2::rect::Rect;
+
+/// npc.tbl 한 항목의 크기 (bits 2, life 2, surf/소리/크기 4, exp 4, damage 4, hit 4, view 4)
+const NPC_ENTRY_SIZE: usize = 24;
+
+/// 중심점으로부터 앞/위/뒤/아래로 잰 영역
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NpcRect {
+    pub front: u8,
+    pub top: u8,
+    pub back: u8,
+    pub bottom: u8,
+}
+
+impl NpcRect {
+    pub fn width(&self) -> u32 {
+        self.front as u32 + self.back as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.top as u32 + self.bottom as u32
+    }
+}
+
+/// NPC 종류 하나의 능력치
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NpcEntry {
+    pub bits: u16,
+    pub life: u16,
+    pub surface: u8,
+    pub death_sound: u8,
+    pub hit_sound: u8,
+    pub smoke_size: u8,
+    pub experience: i32,
+    pub damage: i32,
+    pub hitbox: NpcRect,
+    pub display: NpcRect,
+}
+
+impl NpcEntry {
+    /// 스프라이트 좌상단에서 본 충돌 영역
+    pub fn get_hitbox(&self) -> Rect {
+        Rect::new(
+            self.display.front as i32 - self.hitbox.front as i32,
+            self.display.top as i32 - self.hitbox.top as i32,
+            self.hitbox.width(),
+            self.hitbox.height(),
+        )
+    }
+
+    /// 중심점에서 스프라이트 좌상단까지의 거리
+    pub fn get_display_offset(&self) -> (i32, i32) {
+        (self.display.front as i32, self.display.top as i32)
+    }
+}
+
+/// 원본 NPC 능력치 테이블 (npc.tbl)
+/// 파일은 항목별이 아니라 필드별로 모든 NPC의 값이 이어져 있다.
+#[derive(Debug, Clone, Default)]
+pub struct NpcTable {
+    pub entries: Vec<NpcEntry>,
+}
+
+impl NpcTable {
+    pub fn from_file(path: &Path) -> Result<NpcTable> {
+        NpcTable::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<NpcTable> {
+        if !bytes.len().is_multiple_of(NPC_ENTRY_SIZE) {
+            return Err(Error::new(ErrorKind::InvalidData, "npc.tbl has an unexpected size"));
+        }
+
+        let count = bytes.len() / NPC_ENTRY_SIZE;
+        let mut entries = vec![NpcEntry::default(); count];
+
+        // 각 필드 블럭은 (필드 크기 * count) 바이트이다.
+        let mut offset = 0;
+        let mut column = |size: usize| {
+            let block = &bytes[offset..offset + size * count];
+            offset += size * count;
+            block.chunks_exact(size)
+        };
+
+        for (entry, b) in entries.iter_mut().zip(column(2)) {
+            entry.bits = u16::from_le_bytes([b[0], b[1]]);
+        }
+        for (entry, b) in entries.iter_mut().zip(column(2)) {
+            entry.life = u16::from_le_bytes([b[0], b[1]]);
+        }
+        for (entry, b) in entries.iter_mut().zip(column(1)) {
+            entry.surface = b[0];
+        }
+        for (entry, b) in entries.iter_mut().zip(column(1)) {
+            entry.death_sound = b[0];
+        }
+        for (entry, b) in entries.iter_mut().zip(column(1)) {
+            entry.hit_sound = b[0];
+        }
+        for (entry, b) in entries.iter_mut().zip(column(1)) {
+            entry.smoke_size = b[0];
+        }
+        for (entry, b) in entries.iter_mut().zip(column(4)) {
+            entry.experience = i32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for (entry, b) in entries.iter_mut().zip(column(4)) {
+            entry.damage = i32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for (entry, b) in entries.iter_mut().zip(column(4)) {
+            entry.hitbox = NpcRect { front: b[0], top: b[1], back: b[2], bottom: b[3] };
+        }
+        for (entry, b) in entries.iter_mut().zip(column(4)) {
+            entry.display = NpcRect { front: b[0], top: b[1], back: b[2], bottom: b[3] };
+        }
+
+        Ok(NpcTable { entries })
+    }
+
+    pub fn get(&self, npc_type: u16) -> Option<&NpcEntry> {
+        self.entries.get(npc_type as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_fields_column_by_column() {
+        let mut bytes = vec![];
+        // bits, life
+        bytes.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        bytes.extend_from_slice(&[10, 0, 20, 0]);
+        // surface, death_sound, hit_sound, smoke_size
+        bytes.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        // experience, damage
+        bytes.extend_from_slice(&[1, 0, 0, 0, 2, 0, 0, 0]);
+        bytes.extend_from_slice(&[3, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+        // hitbox, display
+        bytes.extend_from_slice(&[4, 5, 4, 3, 6, 6, 6, 6]);
+        bytes.extend_from_slice(&[8, 8, 8, 8, 16, 16, 16, 16]);
+
+        let table = NpcTable::from_bytes(&bytes).unwrap();
+        assert_eq!(table.entries.len(), 2);
+        assert_eq!(
+            table.get(0),
+            Some(&NpcEntry {
+                bits: 0x0201,
+                life: 10,
+                surface: 1,
+                death_sound: 3,
+                hit_sound: 5,
+                smoke_size: 7,
+                experience: 1,
+                damage: 3,
+                hitbox: NpcRect { front: 4, top: 5, back: 4, bottom: 3 },
+                display: NpcRect { front: 8, top: 8, back: 8, bottom: 8 },
+            })
+        );
+
+        let second = table.get(1).unwrap();
+        assert_eq!((second.bits, second.life, second.smoke_size), (0x0403, 20, 8));
+        assert_eq!((second.experience, second.damage), (2, -1));
+        assert!(table.get(2).is_none());
+
+        // 중심은 스프라이트 좌상단에서 (8, 8)이고, 충돌 영역은 중심에서 앞으로 4, 위로 5이다.
+        assert_eq!(table.get(0).unwrap().get_hitbox(), Rect::new(4, 3, 8, 8));
+    }
+
+    #[test]
+    fn rejects_partial_entries() {
+        let error = NpcTable::from_bytes(&[0; NPC_ENTRY_SIZE + 1]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(NpcTable::from_bytes(&[]).unwrap().entries.is_empty());
+    }
+}
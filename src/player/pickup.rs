@@ -0,0 +1,139 @@
+use sdl2::rect::Rect;
+
+use crate::game::interpolate;
+use crate::graphics::{AnimateSprite, AnimationSets, Camera, Renderable, Renderer};
+use crate::physics::Fixed;
+
+use super::{GRAVITY, GRAVITY_CAP};
+
+/// 줍지 않은 아이템이 사라지기까지의 시간 (ms)
+pub const PICKUP_LIFETIME: u32 = 8000;
+/// 사라지기 전 깜빡이기 시작하는 시간 (ms)
+pub const PICKUP_BLINK_TIME: u32 = 1000;
+pub const PICKUP_SIZE: u32 = 16;
+/// 하트 하나가 회복하는 체력
+pub const HEART_AMOUNT: i32 = 2;
+/// 미사일 하나가 채우는 탄약
+pub const MISSILE_AMOUNT: i32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupKind {
+    /// 현재 무기의 경험치
+    Experience(i32),
+    /// 체력 회복
+    Heart(i32),
+    /// 미사일 탄약
+    Missile(i32),
+}
+
+/// 적이 죽을 때 떨어뜨리는 아이템의 확률 (%). 합이 100을 넘지 않아야 한다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DropChance {
+    pub experience: u32,
+    pub heart: u32,
+    pub missile: u32,
+}
+
+impl Default for DropChance {
+    fn default() -> Self {
+        DropChance { experience: 60, heart: 25, missile: 10 }
+    }
+}
+
+impl DropChance {
+    /// 0 ~ 99 사이의 roll에 해당하는 아이템. 경험치가 없는 적은 경험치를 떨어뜨리지 않는다.
+    pub fn pick(&self, roll: u32, experience: i32) -> Option<PickupKind> {
+        if roll < self.experience {
+            (experience > 0).then_some(PickupKind::Experience(experience))
+        } else if roll < self.experience + self.heart {
+            Some(PickupKind::Heart(HEART_AMOUNT))
+        } else if roll < self.experience + self.heart + self.missile {
+            Some(PickupKind::Missile(MISSILE_AMOUNT))
+        } else {
+            None
+        }
+    }
+}
+
+/// 바닥에 떨어져 있다가 플레이어가 닿으면 주워지는 아이템
+#[derive(Clone)]
+pub struct Pickup {
+    animation: AnimateSprite,
+    pub kind: PickupKind,
+    x: Fixed,
+    y: Fixed,
+    /// 직전 틱의 위치(픽셀). 렌더링 보간에 쓴다.
+    previous: (i32, i32),
+    dy: Fixed,
+    lifetime: u32,
+}
+
+impl Pickup {
+    /// (x, y)에 kind 아이템을 놓는다. 종류마다 같은 이름의 애니메이션 세트를 쓴다.
+    pub fn new(x: i32, y: i32, kind: PickupKind, animations: &AnimationSets) -> Self {
+        let animation = animations.get_sprite(match kind {
+            PickupKind::Experience(_) => "experience",
+            PickupKind::Heart(_) => "heart",
+            PickupKind::Missile(_) => "missile",
+        });
+
+        Pickup {
+            animation,
+            kind,
+            x: Fixed::from_pixels(x),
+            y: Fixed::from_pixels(y),
+            previous: (x, y),
+            dy: Fixed::ZERO,
+            lifetime: PICKUP_LIFETIME,
+        }
+    }
+
+    pub fn update(&mut self, dt: u32) {
+        self.previous = self.get_position();
+        if self.dy <= GRAVITY_CAP {
+            self.dy += GRAVITY;
+        }
+        self.y += self.dy * dt as i32;
+
+        self.lifetime = self.lifetime.saturating_sub(dt);
+        self.animation.update(dt);
+    }
+
+    /// 블록 위에 멈춘다.
+    pub fn land(&mut self, blocks: &[Rect]) {
+        if let Some(top) = blocks.iter().map(|block| block.top()).min() {
+            self.y = Fixed::from_pixels(top - PICKUP_SIZE as i32);
+            self.dy = Fixed::ZERO;
+        }
+    }
+
+    /// 픽셀 단위 위치
+    pub fn get_position(&self) -> (i32, i32) {
+        (self.x.to_pixels(), self.y.to_pixels())
+    }
+
+    pub fn get_collision(&self) -> Rect {
+        let (x, y) = self.get_position();
+        Rect::new(x, y, PICKUP_SIZE, PICKUP_SIZE)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.lifetime == 0
+    }
+}
+
+impl Renderable for Pickup {
+    fn get_name(&self) -> String {
+        "npc_sym".into()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32) {
+        if self.lifetime < PICKUP_BLINK_TIME && (self.lifetime / 50).is_multiple_of(2) {
+            return;
+        }
+
+        let (x, y) = interpolate(self.previous, self.get_position(), alpha);
+        let (x, y) = camera.to_screen(x, y);
+        self.animation.render(x, y, renderer);
+    }
+}
@@ -1,14 +1,17 @@
-use sdl2::{
-    rect::Rect,
-    render::{Texture, WindowCanvas},
-};
+use sdl2::rect::Rect;
 
 use crate::{
-    graphics::{level::Slope, AnimateSprite, Door, Rectangle, Renderable},
-    physics::Sides,
+    game::interpolate,
+    graphics::{
+        level::Slope, AnimateSprite, AnimationSets, Camera, Door, Rectangle, Renderable, Renderer,
+        Transform,
+    },
+    physics::{Fixed, Sides},
 };
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use super::{Bullet, Pickup, PickupKind, Weapon};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Direction {
     Up,
     Down,
@@ -31,105 +34,76 @@ impl From<Sides> for Direction {
     }
 }
 
-pub const GRAVITY: f32 = 0.02;
-pub const GRAVITY_CAP: f32 = 0.8;
-pub const WALK_SPEED: f32 = 0.2;
-pub const JUMP_SPPED: f32 = 0.4;
+/// 속도는 1ms 동안 움직이는 거리이다.
+pub const GRAVITY: Fixed = Fixed::from_f32(0.02);
+pub const GRAVITY_CAP: Fixed = Fixed::from_f32(0.8);
+pub const WALK_SPEED: Fixed = Fixed::from_f32(0.2);
+pub const JUMP_SPPED: Fixed = Fixed::from_f32(0.4);
+/// 맞은 뒤 다시 맞지 않는 시간 (ms)
+pub const INVINCIBILITY_DURATION: u32 = 1500;
+/// 맞아서 밀려나는 동안 좌우 조작이 듣지 않는 시간 (ms)
+pub const KNOCKBACK_DURATION: u32 = 200;
+pub const KNOCKBACK_X: Fixed = Fixed::from_f32(0.1);
+pub const KNOCKBACK_Y: Fixed = Fixed::from_f32(0.25);
 
 pub struct Player {
     animation: AnimateSprite,
-    pub x: i32,
-    pub y: i32,
-    dx: f32,
-    dy: f32,
+    pub x: Fixed,
+    pub y: Fixed,
+    /// 직전 틱의 위치(픽셀). 렌더링 보간에 쓴다.
+    previous: (i32, i32),
+    dx: Fixed,
+    dy: Fixed,
     facing: Direction,
     grounded: bool,
     pub collision: Rect,
     looking_up: bool,
     looking_down: bool,
+    /// 이번 틱에 아래 키를 새로 눌러 문이나 물건을 살펴보려 한다.
+    interacting: bool,
     pub max_health: i32,
     pub current_health: i32,
+    /// 남은 무적 시간 (ms)
+    invincible: u32,
+    /// 남은 넉백 시간 (ms)
+    knockback: u32,
+    pub weapons: Vec<Weapon>,
+    pub current_weapon: usize,
+    /// 쏜 총알들. 벽/적과의 충돌은 Game에서 처리한다.
+    pub bullets: Vec<Bullet>,
 }
 
 impl Player {
-    pub fn new(x: i32, y: i32) -> Self {
-        let mut animation = AnimateSprite::new("player".into());
-        animation.add_animation("idle_left".into(), Rect::new(0, 0, 16, 16), 150, false, 1, 1);
-        animation.add_animation("idle_right".into(), Rect::new(0, 16, 16, 16), 150, false, 1, 1);
-        animation.add_animation("move_left".into(), Rect::new(0, 0, 16, 16), 150, false, 3, 1);
-        animation.add_animation("move_right".into(), Rect::new(0, 16, 16, 16), 150, false, 3, 1);
-        animation.add_animation("idle_left_up".into(), Rect::new(48, 0, 16, 16), 150, false, 1, 1);
-        animation.add_animation(
-            "idle_right_up".into(),
-            Rect::new(48, 16, 16, 16),
-            150,
-            false,
-            1,
-            1,
-        );
-        animation.add_animation("move_left_up".into(), Rect::new(48, 0, 16, 16), 150, false, 3, 1);
-        animation.add_animation(
-            "move_right_up".into(),
-            Rect::new(48, 16, 16, 16),
-            150,
-            false,
-            3,
-            1,
-        );
-        animation.add_animation(
-            "look_down_left".into(),
-            Rect::new(96, 0, 16, 16),
-            150,
-            false,
-            1,
-            1,
-        );
-        animation.add_animation(
-            "look_down_right".into(),
-            Rect::new(96, 16, 16, 16),
-            150,
-            false,
-            1,
-            1,
-        );
-        animation.add_animation(
-            "look_backwards_left".into(),
-            Rect::new(112, 0, 16, 16),
-            150,
-            false,
-            1,
-            1,
-        );
-        animation.add_animation(
-            "look_backwards_right".into(),
-            Rect::new(112, 16, 16, 16),
-            150,
-            false,
-            1,
-            1,
-        );
-
-        animation.set_animation("move_left".into());
+    pub fn new(x: i32, y: i32, animations: &AnimationSets) -> Self {
+        let mut animation = animations.get_sprite("player");
+        animation.set_animation("move".into());
 
         Self {
             animation,
-            x,
-            y,
-            dx: 0.0,
-            dy: 0.0,
+            x: Fixed::from_pixels(x),
+            y: Fixed::from_pixels(y),
+            previous: (x, y),
+            dx: Fixed::ZERO,
+            dy: Fixed::ZERO,
             facing: Direction::IdleLeft,
             grounded: false,
-            collision: Rect::new(0, 0, 16, 16),
+            collision: Rect::new(x, y, 16, 16),
             looking_up: false,
             looking_down: false,
+            interacting: false,
             max_health: 3,
             current_health: 2,
+            invincible: 0,
+            knockback: 0,
+            weapons: vec![Weapon::polar_star()],
+            current_weapon: 0,
+            bullets: vec![],
         }
     }
 
     pub fn stop_moving(&mut self) {
-        self.dx = 0.;
-        self.dy = 0.;
+        self.dx = Fixed::ZERO;
+        self.dy = Fixed::ZERO;
     }
 
     pub fn jump(&mut self) {
@@ -140,39 +114,97 @@ impl Player {
     }
 
     pub fn update(&mut self, dt: u32) {
-        self.x += (self.dx * dt as f32) as i32;
+        self.previous = self.get_position();
+        self.x += self.dx * dt as i32;
         // free fall
         if self.dy <= GRAVITY_CAP {
             self.dy += GRAVITY;
         }
-        self.y += (self.dy * dt as f32) as i32;
+        self.y += self.dy * dt as i32;
 
         self.animation.update(dt);
-        self.collision.x = self.x;
-        self.collision.y = self.y;
+        self.update_collision();
+
+        self.invincible = self.invincible.saturating_sub(dt);
+        self.knockback = self.knockback.saturating_sub(dt);
+
+        self.weapons.iter_mut().for_each(|weapon| weapon.update(dt));
+        self.bullets.iter_mut().for_each(|bullet| bullet.update(dt));
+        self.bullets.retain(|bullet| bullet.alive);
+    }
+
+    pub fn get_weapon(&self) -> Option<&Weapon> {
+        self.weapons.get(self.current_weapon)
+    }
+
+    /// 총알이 날아갈 방향. 공중에서 아래를 볼 때만 아래로 쏜다.
+    pub fn get_shoot_direction(&self) -> Direction {
+        if self.looking_up {
+            Direction::Up
+        } else if self.looking_down && !self.grounded {
+            Direction::Down
+        } else {
+            match self.facing {
+                Direction::Right | Direction::IdleRight => Direction::Right,
+                _ => Direction::Left,
+            }
+        }
+    }
+
+    /// 현재 무기로 총을 쏜다. 쿨다운 중이거나 총알이 너무 많으면 쏘지 않는다.
+    pub fn shoot(&mut self) {
+        let direction = self.get_shoot_direction();
+        let (x, y) = self.get_position();
+        // 총구 위치 (총알 중심)
+        let (x, y) = match direction {
+            Direction::Up => (x + 8, y - 4),
+            Direction::Down => (x + 8, y + 20),
+            Direction::Left => (x - 4, y + 10),
+            _ => (x + 20, y + 10),
+        };
+
+        let bullets = self.bullets.len();
+        if let Some(weapon) = self.weapons.get_mut(self.current_weapon) {
+            if weapon.can_fire(bullets) {
+                self.bullets.push(weapon.fire(x, y, direction));
+            }
+        }
+    }
+
+    pub fn next_weapon(&mut self) {
+        if !self.weapons.is_empty() {
+            self.current_weapon = (self.current_weapon + 1) % self.weapons.len();
+        }
+    }
+
+    pub fn previous_weapon(&mut self) {
+        if !self.weapons.is_empty() {
+            self.current_weapon =
+                (self.current_weapon + self.weapons.len() - 1) % self.weapons.len();
+        }
     }
 
     pub fn move_left(&mut self) {
-        if self.looking_down && self.grounded {
+        if self.knockback > 0 || (self.looking_down && self.grounded) {
             return;
         }
 
-        self.dx = WALK_SPEED * -1.;
+        self.dx = -WALK_SPEED;
 
         if !self.looking_up {
-            self.animation.set_animation("move_left".into());
+            self.animation.set_animation("move".into());
         }
         self.facing = Direction::Left;
     }
 
     pub fn move_right(&mut self) {
-        if self.looking_down && self.grounded {
+        if self.knockback > 0 || (self.looking_down && self.grounded) {
             return;
         }
 
         self.dx = WALK_SPEED;
         if !self.looking_up {
-            self.animation.set_animation("move_right".into());
+            self.animation.set_animation("move".into());
         }
 
         self.facing = Direction::Right;
@@ -183,18 +215,18 @@ impl Player {
             return;
         }
 
-        self.dx = WALK_SPEED * vector.0 as f32;
+        self.dx = WALK_SPEED * vector.0;
         // self.dy = WALK_SPEED * vector.1 as f32;
     }
 
     pub fn stop(&mut self) {
-        self.dx = 0.0;
+        if self.knockback > 0 {
+            return;
+        }
+
+        self.dx = Fixed::ZERO;
         if !self.looking_up && !self.looking_down {
-            self.animation.set_animation(if self.facing == Direction::Right {
-                "idle_right".into()
-            } else {
-                "idle_left".into()
-            });
+            self.animation.set_animation("idle".into());
         }
         // self.dy = 0.0;
     }
@@ -204,35 +236,33 @@ impl Player {
             let side = self.collision_side(other);
             match side {
                 Sides::Top => {
-                    self.dy = 0.;
-                    self.y = other.y + other.height() as i32;
+                    self.dy = Fixed::ZERO;
+                    self.y = Fixed::from_pixels(other.y + other.height() as i32);
                     if self.grounded {
-                        self.dx = 0.;
-                        self.x = ((self.x as f32)
-                            - match self.facing {
-                                Direction::Right => 1.0,
-                                _ => -1.0,
-                            }) as i32;
+                        self.dx = Fixed::ZERO;
+                        self.x -= Fixed::from_pixels(match self.facing {
+                            Direction::Right => 1,
+                            _ => -1,
+                        });
                     }
                 }
                 Sides::Bottom => {
-                    self.y = other.y - self.collision.height() as i32;
-                    self.dy = 0.;
+                    self.y = Fixed::from_pixels(other.y - self.collision.height() as i32);
+                    self.dy = Fixed::ZERO;
                     self.grounded = true;
                 }
                 Sides::Left => {
-                    self.x = other.x + other.width() as i32;
-                    self.dx = 0.;
+                    self.x = Fixed::from_pixels(other.x + other.width() as i32);
+                    self.dx = Fixed::ZERO;
                 }
                 Sides::Right => {
-                    self.x = other.x - self.collision.width() as i32;
-                    self.dx = 0.;
+                    self.x = Fixed::from_pixels(other.x - self.collision.width() as i32);
+                    self.dx = Fixed::ZERO;
                 }
                 _ => {}
             }
         }
-        self.collision.x = self.x;
-        self.collision.y = self.y;
+        self.update_collision();
     }
 
     pub fn handle_slope_collision(&mut self, slopes: &[Slope]) {
@@ -241,29 +271,89 @@ impl Player {
 
         for slope in slopes {
             let b = slope.from.top() - slope.get_slope() * slope.from.left().abs();
+            if slope.ceiling {
+                // 천장 기울기는 머리가 선 위로 올라가지 못하게 밀어낸다.
+                let ceiling_y = slope.get_slope() * center_x + b;
+                if bounce_rect.top < ceiling_y {
+                    self.y = Fixed::from_f32(ceiling_y);
+                    self.dy = self.dy.max(Fixed::ZERO);
+                }
+                continue;
+            }
+
             let new_y = slope.get_slope() * center_x + b - 4.0;
             if self.grounded {
-                self.y = (new_y - bounce_rect.height) as i32;
+                self.y = Fixed::from_f32(new_y - bounce_rect.height);
             }
             self.grounded = true;
         }
+        self.update_collision();
     }
 
-    pub fn handle_door_collision(&mut self, doors: &[Door]) -> String {
-        let mut level: String = "".into();
-        for door in doors {
-            if self.grounded && self.looking_down {
-                level = door.destination.clone();
-            }
+    /// 바닥에서 아래 키를 새로 눌렀으면 들어갈 문
+    /// 아래 키를 누르고 있는 것만으로는 들어가지 않으므로, 문으로 나온 맵에서 다시 되돌아가지 않는다.
+    pub fn handle_door_collision(&mut self, doors: &[Door]) -> Option<Door> {
+        if self.take_interaction() {
+            doors.last().cloned()
+        } else {
+            None
         }
-        level
+    }
+
+    /// 아래 키를 새로 눌렀을 때. 다음 update의 이벤트나 문 충돌에서 한 번만 쓰인다.
+    pub fn interact(&mut self) {
+        self.interacting = true;
+    }
+
+    /// 바닥에 서서 살펴보려 하는가
+    pub fn is_interacting(&self) -> bool {
+        self.interacting && self.grounded
+    }
+
+    /// is_interacting과 같지만 한 번 가져가면 지워진다.
+    pub fn take_interaction(&mut self) -> bool {
+        let interacting = self.is_interacting();
+        self.interacting = false;
+        interacting
+    }
+
+    /// 새 맵의 (x, y)에 나타난다. 체력, 무기, 속도는 그대로이고 이전 맵에 쏜 총알은 사라진다.
+    /// facing이 있으면 그 방향을 바라본다.
+    pub fn enter_map(&mut self, x: i32, y: i32, facing: Option<Direction>) {
+        self.x = Fixed::from_pixels(x);
+        self.y = Fixed::from_pixels(y);
+        self.previous = (x, y);
+        self.grounded = false;
+        self.looking_down = false;
+        self.interacting = false;
+        self.bullets.clear();
+        self.update_collision();
+
+        if let Some(facing) = facing {
+            self.facing = facing;
+            let moving = if self.dx == Fixed::ZERO { "idle" } else { "move" };
+            self.animation.set_animation(moving.into());
+        }
+    }
+
+    /// 픽셀 단위 위치
+    pub fn get_position(&self) -> (i32, i32) {
+        (self.x.to_pixels(), self.y.to_pixels())
+    }
+
+    /// 충돌 영역을 현재 위치로 옮긴다.
+    fn update_collision(&mut self) {
+        let (x, y) = self.get_position();
+        self.collision.x = x;
+        self.collision.y = y;
     }
 
     fn collision_side(&self, other: &Rect) -> Sides {
-        let amt_right = self.x + self.collision.width() as i32 - other.x;
-        let amt_left = other.x + other.width() as i32 - self.x;
-        let amt_top = other.y + other.height() as i32 - self.y;
-        let amt_bottom = self.y + self.collision.height() as i32 - other.y;
+        let (x, y) = self.get_position();
+        let amt_right = x + self.collision.width() as i32 - other.x;
+        let amt_left = other.x + other.width() as i32 - x;
+        let amt_top = other.y + other.height() as i32 - y;
+        let amt_bottom = y + self.collision.height() as i32 - other.y;
 
         let mut vals = vec![amt_right.abs(), amt_left.abs(), amt_top.abs(), amt_bottom.abs()];
         vals.sort_unstable();
@@ -286,16 +376,10 @@ impl Player {
     /// The player Lookup
     pub fn look_up(&mut self) {
         self.looking_up = true;
-        if self.dx == 0. {
-            self.animation.set_animation(match self.facing {
-                Direction::Right => "idle_right_up".into(),
-                _ => "idle_left_up".into(),
-            });
+        if self.dx == Fixed::ZERO {
+            self.animation.set_animation("idle_up".into());
         } else {
-            self.animation.set_animation(match self.facing {
-                Direction::Right => "move_right_up".into(),
-                _ => "move_left_up".into(),
-            });
+            self.animation.set_animation("move_up".into());
         }
     }
 
@@ -308,15 +392,9 @@ impl Player {
     pub fn look_down(&mut self) {
         self.looking_down = true;
         if self.grounded {
-            self.animation.set_animation(match self.facing {
-                Direction::Right => "look_backwards_right".into(),
-                _ => "look_backwards_left".into(),
-            });
+            self.animation.set_animation("look_backwards".into());
         } else {
-            self.animation.set_animation(match self.facing {
-                Direction::Right => "look_down_right".into(),
-                _ => "look_down_left".into(),
-            });
+            self.animation.set_animation("look_down".into());
         }
     }
 
@@ -325,8 +403,74 @@ impl Player {
         self.looking_down = false;
     }
 
-    pub fn take_damage(&mut self, amount: i32) {
+    /// 카메라가 앞세울 방향. x는 바라보는 방향, y는 위/아래를 보고 있을 때만 -1/1
+    pub fn get_look_direction(&self) -> (i32, i32) {
+        let x = match self.facing {
+            Direction::Right | Direction::IdleRight => 1,
+            _ => -1,
+        };
+        let y = if self.looking_up {
+            -1
+        } else if self.looking_down {
+            1
+        } else {
+            0
+        };
+
+        (x, y)
+    }
+
+    /// source_x 쪽에서 맞아 반대쪽으로 튕겨 나간다. 무적 시간에는 맞지 않고 false를 반환한다.
+    /// 다치면 현재 무기가 받은 피해의 두 배만큼 경험치를 잃는다.
+    pub fn take_damage(&mut self, amount: i32, source_x: i32) -> bool {
+        if amount <= 0 || self.invincible > 0 || self.is_dead() {
+            return false;
+        }
+
         self.current_health = 0.max(self.current_health - amount);
+        if let Some(weapon) = self.weapons.get_mut(self.current_weapon) {
+            weapon.lose_experience(amount * 2);
+        }
+
+        self.invincible = INVINCIBILITY_DURATION;
+        self.knockback = KNOCKBACK_DURATION;
+        let center_x = self.collision.x + self.collision.width() as i32 / 2;
+        self.dx = if source_x > center_x { -KNOCKBACK_X } else { KNOCKBACK_X };
+        self.dy = -KNOCKBACK_Y;
+        self.grounded = false;
+        true
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current_health <= 0
+    }
+
+    pub fn is_invincible(&self) -> bool {
+        self.invincible > 0
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// 지난번 이후로 스프라이트 애니메이션에서 일어난 이벤트 (footstep 등)
+    pub fn take_animation_events(&mut self) -> Vec<String> {
+        self.animation.take_events()
+    }
+
+    pub fn collect(&mut self, pickup: &Pickup) {
+        match pickup.kind {
+            PickupKind::Experience(amount) => {
+                if let Some(weapon) = self.weapons.get_mut(self.current_weapon) {
+                    weapon.add_experience(amount);
+                }
+            }
+            PickupKind::Heart(amount) => {
+                self.current_health = self.max_health.min(self.current_health + amount);
+            }
+            // 아직 탄약을 쓰는 무기가 없다.
+            PickupKind::Missile(_) => {}
+        }
     }
 }
 
@@ -335,7 +479,24 @@ impl Renderable for Player {
         "player".into()
     }
 
-    fn render(&self, canvas: &mut WindowCanvas, texture: &Texture) {
-        self.animation.render(self.x, self.y, canvas, texture);
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32) {
+        // 무적 시간에는 깜빡인다.
+        if self.is_dead() || !(self.invincible / 50).is_multiple_of(2) {
+            return;
+        }
+
+        let (x, y) = interpolate(self.previous, self.get_position(), alpha);
+        let (x, y) = camera.to_screen(x, y);
+        // 스프라이트는 왼쪽을 보는 것만 있으므로 오른쪽을 볼 때는 뒤집어 그린다.
+        let facing_right = matches!(self.facing, Direction::Right | Direction::IdleRight);
+        self.animation.render_transformed(x, y, renderer, Transform::flip(facing_right, false));
+
+        // 무기는 24px 폭이라 왼쪽을 볼 때는 8px 왼쪽으로 삐져나온다.
+        if let Some(weapon) = self.get_weapon() {
+            let source = weapon.get_arms_rect(facing_right, self.get_shoot_direction());
+            let dest =
+                Rect::new(if facing_right { x } else { x - 8 }, y, source.width(), source.height());
+            renderer.copy("arms", source, dest, Transform::default());
+        }
     }
 }
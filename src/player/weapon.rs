@@ -0,0 +1,258 @@
+use sdl2::rect::Rect;
+
+use crate::game::interpolate;
+use crate::graphics::{Camera, Renderable, Renderer, Sprite};
+use crate::physics::Fixed;
+
+use super::Direction;
+
+/// Arms.pbm에서 무기 하나가 차지하는 폭. 무기 번호 * ARMS_WIDTH 가 x 위치이다.
+pub const ARMS_WIDTH: i32 = 24;
+pub const ARMS_HEIGHT: i32 = 16;
+/// 총알 스프라이트 크기 (Bullet.pbm)
+pub const BULLET_SIZE: i32 = 16;
+/// 총알의 충돌 영역 크기. 스프라이트 가운데에 놓인다.
+pub const BULLET_HITBOX: i32 = 6;
+
+/// 무기 최고 레벨
+pub const MAX_WEAPON_LEVEL: usize = 3;
+
+/// 무기 레벨 하나의 성능
+#[derive(Debug, Clone)]
+pub struct WeaponLevel {
+    pub damage: i32,
+    /// 총알이 사라지기 전까지 날아가는 거리 (px)
+    pub range: i32,
+    /// 다음 레벨까지 필요한 경험치. 최고 레벨에서는 MAX 가 되는 경험치이다.
+    pub max_experience: i32,
+    /// 좌우로 날아가는 총알과 위아래로 날아가는 총알의 Bullet.pbm 영역
+    pub horizontal_sprite: Rect,
+    pub vertical_sprite: Rect,
+}
+
+/// 무기 한 종류의 성능과 경험치
+#[derive(Debug, Clone)]
+pub struct Weapon {
+    pub name: String,
+    /// Arms.pbm 에서의 무기 번호
+    pub arms_index: i32,
+    /// 총알 속도 (1ms 동안 움직이는 거리)
+    pub speed: Fixed,
+    /// 다음 총알을 쏠 수 있을 때까지의 시간 (ms)
+    pub fire_rate: u32,
+    /// 화면에 동시에 있을 수 있는 총알 수
+    pub max_bullets: usize,
+    /// 레벨 1부터 MAX_WEAPON_LEVEL 까지의 성능
+    pub levels: [WeaponLevel; MAX_WEAPON_LEVEL],
+    /// 1부터 시작한다.
+    pub level: usize,
+    pub experience: i32,
+    cooldown: u32,
+}
+
+impl Weapon {
+    pub fn polar_star() -> Self {
+        Weapon {
+            name: "Polar Star".into(),
+            arms_index: 2,
+            speed: Fixed::from_f32(0.4),
+            fire_rate: 100,
+            max_bullets: 2,
+            levels: [
+                WeaponLevel {
+                    damage: 1,
+                    range: 80,
+                    max_experience: 10,
+                    horizontal_sprite: Rect::new(128, 32, 16, 16),
+                    vertical_sprite: Rect::new(144, 32, 16, 16),
+                },
+                WeaponLevel {
+                    damage: 2,
+                    range: 96,
+                    max_experience: 20,
+                    horizontal_sprite: Rect::new(160, 32, 16, 16),
+                    vertical_sprite: Rect::new(176, 32, 16, 16),
+                },
+                WeaponLevel {
+                    damage: 4,
+                    range: 112,
+                    max_experience: 10,
+                    horizontal_sprite: Rect::new(128, 48, 16, 16),
+                    vertical_sprite: Rect::new(144, 48, 16, 16),
+                },
+            ],
+            level: 1,
+            experience: 0,
+            cooldown: 0,
+        }
+    }
+
+    pub fn update(&mut self, dt: u32) {
+        self.cooldown = self.cooldown.saturating_sub(dt);
+    }
+
+    /// 현재 레벨의 성능
+    pub fn get_level(&self) -> &WeaponLevel {
+        &self.levels[self.level - 1]
+    }
+
+    /// 경험치를 얻는다. 다음 레벨 경험치를 넘으면 레벨이 오르고 남은 경험치는 넘어간다.
+    /// 최고 레벨에서는 MAX 에서 멈춘다.
+    pub fn add_experience(&mut self, amount: i32) {
+        self.experience += amount;
+        while self.experience >= self.get_level().max_experience {
+            if self.level == MAX_WEAPON_LEVEL {
+                self.experience = self.get_level().max_experience;
+                break;
+            }
+            self.experience -= self.get_level().max_experience;
+            self.level += 1;
+        }
+    }
+
+    /// 경험치를 잃는다. 0 아래로 내려가면 레벨이 떨어진다. 레벨 1의 0 아래로는 내려가지 않는다.
+    pub fn lose_experience(&mut self, amount: i32) {
+        self.experience -= amount;
+        while self.experience < 0 {
+            if self.level == 1 {
+                self.experience = 0;
+                break;
+            }
+            self.level -= 1;
+            self.experience += self.get_level().max_experience;
+        }
+    }
+
+    /// 최고 레벨에서 경험치가 가득 찼는가
+    pub fn is_max(&self) -> bool {
+        self.level == MAX_WEAPON_LEVEL && self.experience >= self.get_level().max_experience
+    }
+
+    /// 다음 레벨까지 채운 비율 (0 ~ 1)
+    pub fn get_experience_ratio(&self) -> f32 {
+        self.experience as f32 / self.get_level().max_experience as f32
+    }
+
+    pub fn can_fire(&self, bullets: usize) -> bool {
+        self.cooldown == 0 && bullets < self.max_bullets
+    }
+
+    /// (x, y)를 중심으로 direction 방향의 총알을 만든다.
+    pub fn fire(&mut self, x: i32, y: i32, direction: Direction) -> Bullet {
+        self.cooldown = self.fire_rate;
+        Bullet::new(self, x, y, direction)
+    }
+
+    /// 무기 스프라이트의 Arms.pbm 영역. 위/아래를 볼 때는 각각 32, 64 아래 줄을 쓴다.
+    pub fn get_arms_rect(&self, facing_right: bool, direction: Direction) -> Rect {
+        let mut y = if facing_right { ARMS_HEIGHT } else { 0 };
+        match direction {
+            Direction::Up => y += ARMS_HEIGHT * 2,
+            Direction::Down => y += ARMS_HEIGHT * 4,
+            _ => {}
+        }
+
+        Rect::new(self.arms_index * ARMS_WIDTH, y, ARMS_WIDTH as u32, ARMS_HEIGHT as u32)
+    }
+}
+
+/// 날아가는 총알
+#[derive(Debug, Clone)]
+pub struct Bullet {
+    sprite: Sprite,
+    /// 스프라이트 좌상단 위치
+    x: Fixed,
+    y: Fixed,
+    /// 직전 틱의 위치(픽셀). 렌더링 보간에 쓴다.
+    previous: (i32, i32),
+    dx: Fixed,
+    dy: Fixed,
+    direction: Direction,
+    /// 지금까지 날아간 거리
+    distance: Fixed,
+    range: Fixed,
+    pub damage: i32,
+    pub alive: bool,
+}
+
+impl Bullet {
+    pub fn new(weapon: &Weapon, x: i32, y: i32, direction: Direction) -> Self {
+        let level = weapon.get_level();
+        let (dx, dy, source) = match direction {
+            Direction::Up => (Fixed::ZERO, -weapon.speed, level.vertical_sprite),
+            Direction::Down => (Fixed::ZERO, weapon.speed, level.vertical_sprite),
+            Direction::Left => (-weapon.speed, Fixed::ZERO, level.horizontal_sprite),
+            _ => (weapon.speed, Fixed::ZERO, level.horizontal_sprite),
+        };
+        let (x, y) = (x - BULLET_SIZE / 2, y - BULLET_SIZE / 2);
+
+        Bullet {
+            sprite: Sprite::new(
+                "bullet".into(),
+                source.x,
+                source.y,
+                source.width(),
+                source.height(),
+            ),
+            x: Fixed::from_pixels(x),
+            y: Fixed::from_pixels(y),
+            previous: (x, y),
+            dx,
+            dy,
+            direction,
+            distance: Fixed::ZERO,
+            range: Fixed::from_pixels(level.range),
+            damage: level.damage,
+            alive: true,
+        }
+    }
+
+    /// 사거리를 넘으면 사라진다.
+    pub fn update(&mut self, dt: u32) {
+        self.previous = self.get_position();
+        self.x += self.dx * dt as i32;
+        self.y += self.dy * dt as i32;
+
+        self.distance += (self.dx.abs() + self.dy.abs()) * dt as i32;
+        if self.distance >= self.range {
+            self.alive = false;
+        }
+    }
+
+    /// 픽셀 단위 위치
+    pub fn get_position(&self) -> (i32, i32) {
+        (self.x.to_pixels(), self.y.to_pixels())
+    }
+
+    /// 총알 중심 위치. 맞은 자리에 효과를 낼 때 쓴다.
+    pub fn get_center(&self) -> (i32, i32) {
+        let (x, y) = self.get_position();
+        (x + BULLET_SIZE / 2, y + BULLET_SIZE / 2)
+    }
+
+    pub fn get_collision(&self) -> Rect {
+        let (x, y) = self.get_center();
+        Rect::new(
+            x - BULLET_HITBOX / 2,
+            y - BULLET_HITBOX / 2,
+            BULLET_HITBOX as u32,
+            BULLET_HITBOX as u32,
+        )
+    }
+
+    pub fn get_direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+impl Renderable for Bullet {
+    fn get_name(&self) -> String {
+        "bullet".into()
+    }
+
+    fn render(&self, renderer: &mut dyn Renderer, camera: &Camera, alpha: f32) {
+        let (x, y) = interpolate(self.previous, self.get_position(), alpha);
+        let (x, y) = camera.to_screen(x, y);
+        self.sprite.render(x, y, renderer);
+    }
+}
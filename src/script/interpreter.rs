@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+use crate::graphics::DialogueBox;
+
+use super::{TscScript, TscToken};
+
+/// 원본은 50fps 기준으로 <WAI를 세므로 한 틱은 20ms 이다.
+pub const TSC_TICK: u32 = 20;
+
+/// <TRA 명령으로 요청된 맵 이동
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transfer {
+    pub map: u16,
+    pub event: u16,
+    /// 타일 단위 좌표
+    pub x: u16,
+    pub y: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptResult {
+    None,
+    Transfer(Transfer),
+}
+
+/// TSC 이벤트를 한 프레임씩 진행시키는 해석기
+#[derive(Default)]
+pub struct TscInterpreter {
+    pub script: TscScript,
+    event: Option<u16>,
+    position: usize,
+    wait: u32,
+    waiting_for_key: bool,
+    /// <YNJ 에서 아니오를 골랐을 때 이동할 이벤트
+    prompt_event: Option<u16>,
+    pub flags: HashSet<u16>,
+    pub dialogue: DialogueBox,
+    /// <KEY, <PRI 중에는 플레이어를 조작할 수 없다.
+    pub input_locked: bool,
+    /// <PRI 중에는 게임 세계도 멈춘다.
+    pub world_frozen: bool,
+}
+
+impl TscInterpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 새 스크립트로 바꾼다. 플래그는 그대로 유지된다.
+    pub fn load(&mut self, script: TscScript) {
+        self.script = script;
+        self.end();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.event.is_some()
+    }
+
+    pub fn is_waiting_for_key(&self) -> bool {
+        self.waiting_for_key
+    }
+
+    pub fn start_event(&mut self, number: u16) {
+        if self.script.get_event(number).is_some() {
+            self.event = Some(number);
+            self.position = 0;
+            self.wait = 0;
+            self.waiting_for_key = false;
+            self.prompt_event = None;
+        } else {
+            self.end();
+        }
+    }
+
+    /// 결정 키를 눌렀을 때.
+    /// 글자가 찍히는 중이면 모두 찍고, 선택지가 있으면 고르고, <NOD 에서 기다리고 있다면 진행시킨다.
+    pub fn confirm(&mut self) {
+        if self.dialogue.is_typing() {
+            self.dialogue.skip();
+        } else if let Some(event) = self.prompt_event.take() {
+            if !self.dialogue.close_prompt() {
+                self.start_event(event);
+            }
+        } else {
+            self.waiting_for_key = false;
+        }
+    }
+
+    /// <YNJ 선택지에서 예/아니오를 고른다.
+    pub fn select(&mut self, yes: bool) {
+        self.dialogue.select(yes);
+    }
+
+    pub fn get_flag(&self, flag: u16) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    pub fn set_flag(&mut self, flag: u16) {
+        self.flags.insert(flag);
+    }
+
+    pub fn clear_flag(&mut self, flag: u16) {
+        self.flags.remove(&flag);
+    }
+
+    pub fn update(&mut self, dt: u32) -> ScriptResult {
+        // 대화창에 글자가 다 찍힐 때까지 다음 명령을 실행하지 않는다.
+        self.dialogue.update(dt);
+        if self.dialogue.is_typing() {
+            return ScriptResult::None;
+        }
+
+        if self.wait > 0 {
+            self.wait = self.wait.saturating_sub(dt);
+            if self.wait > 0 {
+                return ScriptResult::None;
+            }
+        }
+
+        // 기다려야 하는 명령이 나올 때까지 한 프레임에 이어서 실행한다.
+        while let Some(number) = self.event {
+            if self.waiting_for_key
+                || self.prompt_event.is_some()
+                || self.wait > 0
+                || self.dialogue.is_typing()
+            {
+                break;
+            }
+
+            let token = match self
+                .script
+                .get_event(number)
+                .and_then(|event| event.tokens.get(self.position))
+            {
+                Some(token) => token.clone(),
+                None => {
+                    self.end();
+                    break;
+                }
+            };
+            self.position += 1;
+
+            match token {
+                TscToken::Text(text) => {
+                    if self.dialogue.visible {
+                        self.dialogue.push_text(&String::from_utf8_lossy(&text));
+                    }
+                }
+                TscToken::NewLine => {
+                    if self.dialogue.visible {
+                        self.dialogue.new_line();
+                    }
+                }
+                TscToken::Command { name, args } => {
+                    let arg = |n: usize| args.get(n).copied().unwrap_or(0);
+                    match name.as_str() {
+                        "MSG" | "MS2" | "MS3" => self.dialogue.open(),
+                        "CLR" => self.dialogue.clear(),
+                        "CLO" => self.dialogue.close(),
+                        "FAC" => self.dialogue.set_face(Some(arg(0)).filter(|face| *face != 0)),
+                        "YNJ" => {
+                            self.dialogue.show_prompt();
+                            self.prompt_event = Some(arg(0));
+                        }
+                        "WAI" => self.wait = arg(0) as u32 * TSC_TICK,
+                        "NOD" => self.waiting_for_key = true,
+                        "END" => self.end(),
+                        "EVE" | "JMP" => self.start_event(arg(0)),
+                        "FL+" => self.set_flag(arg(0)),
+                        "FL-" => self.clear_flag(arg(0)),
+                        "FLJ" if self.get_flag(arg(0)) => self.start_event(arg(1)),
+                        "KEY" => {
+                            self.input_locked = true;
+                            self.world_frozen = false;
+                        }
+                        "PRI" => {
+                            self.input_locked = true;
+                            self.world_frozen = true;
+                        }
+                        "FRE" => {
+                            self.input_locked = false;
+                            self.world_frozen = false;
+                        }
+                        "TRA" => {
+                            // 이동한 맵에서 event를 이어서 실행하므로 잠금은 풀지 않는다.
+                            self.event = None;
+                            return ScriptResult::Transfer(Transfer {
+                                map: arg(0),
+                                event: arg(1),
+                                x: arg(2),
+                                y: arg(3),
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        ScriptResult::None
+    }
+
+    /// 이벤트를 끝내고 메시지 창과 잠금을 모두 푼다.
+    pub fn end(&mut self) {
+        self.event = None;
+        self.position = 0;
+        self.wait = 0;
+        self.waiting_for_key = false;
+        self.prompt_event = None;
+        self.dialogue.close();
+        self.input_locked = false;
+        self.world_frozen = false;
+    }
+}
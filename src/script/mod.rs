@@ -0,0 +1,5 @@
+pub mod interpreter;
+pub mod tsc;
+
+pub use interpreter::*;
+pub use tsc::*;
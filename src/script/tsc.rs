@@ -0,0 +1,324 @@
+use std::fs;
+use std::io::Result;
+use std::path::Path;
+
+/// 가운데 바이트가 0일 때 대신 쓰는 키 (-7)
+const DEFAULT_KEY: u8 = 0xF9;
+
+/// 명령어 이름별 인자 개수
+/// 표에 없는 명령어는 `:`로 이어진 4자리 숫자를 모두 인자로 읽는다.
+pub fn get_argument_count(name: &str) -> Option<usize> {
+    let count = match name {
+        "AE+" | "CAT" | "CIL" | "CLO" | "CLR" | "CPS" | "CRE" | "CSS" | "END" | "ESC" | "FLA"
+        | "FMU" | "FRE" | "HMC" | "INI" | "KEY" | "LDP" | "MLP" | "MM0" | "MNA" | "MS2" | "MS3"
+        | "MSG" | "NOD" | "PRI" | "RMU" | "SAT" | "SLP" | "SMC" | "SPS" | "STC" | "SVP" | "TUR"
+        | "WAS" | "ZAM" => 0,
+        "AM-" | "BOA" | "BSL" | "CMU" | "DNA" | "DNP" | "EQ+" | "EQ-" | "EVE" | "FAC" | "FAI"
+        | "FAO" | "FL+" | "FL-" | "FOM" | "GIT" | "IT+" | "IT-" | "LI+" | "ML+" | "MP+" | "MPJ"
+        | "MYB" | "MYD" | "NUM" | "QUA" | "SIL" | "SK+" | "SK-" | "SOU" | "SSS" | "UNI" | "WAI"
+        | "XX1" | "YNJ" | "JMP" => 1,
+        "AM+" | "AMJ" | "ECJ" | "FLJ" | "FOB" | "FON" | "ITJ" | "MOV" | "NCJ" | "PS+" | "SKJ"
+        | "SMP" => 2,
+        "ANP" | "CMP" | "CNP" | "INP" | "TAM" => 3,
+        "MNP" | "SNP" | "TRA" => 4,
+        _ => return None,
+    };
+    Some(count)
+}
+
+/// TSC 파일의 암호를 푼다.
+/// 파일 가운데 바이트가 키이며, 나머지 모든 바이트에서 키를 뺀다.
+pub fn decrypt(bytes: &[u8]) -> Vec<u8> {
+    apply_key(bytes, |byte, key| byte.wrapping_sub(key))
+}
+
+/// decrypt의 반대. 평문의 가운데 바이트를 키로 삼아 나머지 바이트에 더한다.
+pub fn encrypt(bytes: &[u8]) -> Vec<u8> {
+    apply_key(bytes, |byte, key| byte.wrapping_add(key))
+}
+
+fn apply_key(bytes: &[u8], op: fn(u8, u8) -> u8) -> Vec<u8> {
+    if bytes.is_empty() {
+        return vec![];
+    }
+
+    let middle = bytes.len() / 2;
+    let key = if bytes[middle] == 0 { DEFAULT_KEY } else { bytes[middle] };
+
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| if i == middle { byte } else { op(byte, key) })
+        .collect()
+}
+
+/// 이벤트 안의 토큰 하나
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TscToken {
+    /// `<XXX` 명령어와 4자리 숫자 인자들
+    Command { name: String, args: Vec<u16> },
+    /// 메시지 글자. 일본어 원본은 Shift-JIS 이므로 바이트 그대로 둔다.
+    Text(Vec<u8>),
+    /// 줄바꿈 (\r\n)
+    NewLine,
+}
+
+impl TscToken {
+    pub fn is_command(&self, command: &str) -> bool {
+        matches!(self, TscToken::Command { name, .. } if name == command)
+    }
+
+    /// n 번째 인자. 없으면 0
+    pub fn get_arg(&self, n: usize) -> u16 {
+        match self {
+            TscToken::Command { args, .. } => args.get(n).copied().unwrap_or(0),
+            _ => 0,
+        }
+    }
+}
+
+/// `#NNNN`으로 시작하는 이벤트 하나
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TscEvent {
+    pub number: u16,
+    pub tokens: Vec<TscToken>,
+}
+
+/// 이벤트 단위로 나눈 스크립트
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TscScript {
+    pub events: Vec<TscEvent>,
+}
+
+impl TscScript {
+    /// 암호화된 .tsc 파일을 읽는다.
+    pub fn from_file(path: &Path) -> Result<TscScript> {
+        Ok(TscScript::parse(&decrypt(&fs::read(path)?)))
+    }
+
+    /// 암호가 풀린 스크립트를 이벤트와 토큰으로 나눈다.
+    /// 첫 번째 `#` 앞의 내용은 무시한다.
+    pub fn parse(text: &[u8]) -> TscScript {
+        let mut events: Vec<TscEvent> = vec![];
+        let mut text_buffer: Vec<u8> = vec![];
+        let mut i = 0;
+
+        while i < text.len() {
+            let at_line_start = i == 0 || text[i - 1] == b'\n';
+
+            if at_line_start && text[i] == b'#' {
+                flush_text(&mut events, &mut text_buffer);
+                let number = read_number(&text[i + 1..]);
+                events.push(TscEvent { number, tokens: vec![] });
+
+                // 이벤트 번호 뒤의 줄바꿈은 토큰으로 남기지 않는다.
+                while i < text.len() && text[i] != b'\n' {
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+
+            match text[i] {
+                b'<' if i + 4 <= text.len() => {
+                    flush_text(&mut events, &mut text_buffer);
+                    let name = String::from_utf8_lossy(&text[i + 1..i + 4]).to_string();
+                    i += 4;
+
+                    let count = get_argument_count(&name);
+                    let mut args = vec![];
+                    loop {
+                        if count.is_some_and(|count| args.len() >= count) || i + 4 > text.len() {
+                            break;
+                        }
+                        if count.is_none() && !is_number(&text[i..i + 4]) {
+                            break;
+                        }
+
+                        args.push(read_number(&text[i..]));
+                        i += 4;
+
+                        // 인자 사이에는 ':' 한 글자가 들어간다.
+                        let has_next = match count {
+                            Some(count) => args.len() < count,
+                            None => {
+                                text.get(i) == Some(&b':')
+                                    && text.get(i + 1..i + 5).is_some_and(is_number)
+                            }
+                        };
+                        if !has_next {
+                            break;
+                        }
+                        i += 1;
+                    }
+
+                    if let Some(event) = events.last_mut() {
+                        event.tokens.push(TscToken::Command { name, args });
+                    }
+                }
+                b'\r' => i += 1,
+                b'\n' => {
+                    flush_text(&mut events, &mut text_buffer);
+                    if let Some(event) = events.last_mut() {
+                        event.tokens.push(TscToken::NewLine);
+                    }
+                    i += 1;
+                }
+                byte => {
+                    text_buffer.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        flush_text(&mut events, &mut text_buffer);
+
+        TscScript { events }
+    }
+
+    pub fn get_event(&self, number: u16) -> Option<&TscEvent> {
+        self.events.iter().find(|event| event.number == number)
+    }
+
+    /// 다시 평문 스크립트로 만든다. encrypt 후 파일로 쓰면 원본과 같은 형식이 된다.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        for event in &self.events {
+            bytes.extend_from_slice(format!("#{:04}\r\n", event.number).as_bytes());
+            for token in &event.tokens {
+                match token {
+                    TscToken::Command { name, args } => {
+                        bytes.push(b'<');
+                        bytes.extend_from_slice(name.as_bytes());
+                        for (i, arg) in args.iter().enumerate() {
+                            if i > 0 {
+                                bytes.push(b':');
+                            }
+                            write_number(&mut bytes, *arg);
+                        }
+                    }
+                    TscToken::Text(text) => bytes.extend_from_slice(text),
+                    TscToken::NewLine => bytes.extend_from_slice(b"\r\n"),
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// 암호화해서 .tsc 파일로 저장한다.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, encrypt(&self.to_bytes()))
+    }
+}
+
+fn flush_text(events: &mut [TscEvent], text_buffer: &mut Vec<u8>) {
+    if text_buffer.is_empty() {
+        return;
+    }
+
+    if let Some(event) = events.last_mut() {
+        event.tokens.push(TscToken::Text(text_buffer.clone()));
+    }
+    text_buffer.clear();
+}
+
+/// 원본과 같이 4 글자를 각 자리의 ('0' 과의 차이)로 계산한다.
+fn read_number(text: &[u8]) -> u16 {
+    text.iter().take(4).fold(0, |value: i32, &byte| value * 10 + (byte as i32 - b'0' as i32)) as u16
+}
+
+/// read_number의 반대. 원본 스크립트에는 숫자가 아닌 글자가 섞인 인자도 있으므로
+/// 9999 보다 큰 값은 천의 자리에 '9' 다음 글자들을 써서 같은 값으로 읽히게 한다.
+fn write_number(bytes: &mut Vec<u8>, value: u16) {
+    bytes.push(b'0' + (value / 1000) as u8);
+    bytes.extend_from_slice(format!("{:03}", value % 1000).as_bytes());
+}
+
+fn is_number(text: &[u8]) -> bool {
+    text.iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_undoes_encrypt() {
+        let plain = b"#0100\r\n<MSGHello<NOD<END\r\n".to_vec();
+        let encrypted = encrypt(&plain);
+
+        assert_ne!(encrypted, plain);
+        // 키인 가운데 바이트는 그대로 남는다.
+        assert_eq!(encrypted[plain.len() / 2], plain[plain.len() / 2]);
+        assert_eq!(decrypt(&encrypted), plain);
+        assert_eq!(decrypt(&[]), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn zero_middle_byte_uses_default_key() {
+        let encrypted = [0x10, 0x00, 0x20];
+        assert_eq!(
+            decrypt(&encrypted),
+            [0x10u8.wrapping_sub(DEFAULT_KEY), 0x00, 0x20u8.wrapping_sub(DEFAULT_KEY)]
+        );
+        assert_eq!(encrypt(&decrypt(&encrypted)), encrypted);
+    }
+
+    #[test]
+    fn splits_events_and_tokens() {
+        let script = TscScript::parse(
+            b"ignored\r\n#0100\r\n<MSGHi<NOD<END\r\n#0200\r\n<TRA0012:0090:0003:0004",
+        );
+
+        assert_eq!(script.events.len(), 2);
+        assert_eq!(
+            script.get_event(100).unwrap().tokens,
+            [
+                TscToken::Command { name: "MSG".into(), args: vec![] },
+                TscToken::Text(b"Hi".to_vec()),
+                TscToken::Command { name: "NOD".into(), args: vec![] },
+                TscToken::Command { name: "END".into(), args: vec![] },
+                TscToken::NewLine,
+            ]
+        );
+
+        let transfer = &script.get_event(200).unwrap().tokens[0];
+        assert!(transfer.is_command("TRA"));
+        assert_eq!((0..4).map(|n| transfer.get_arg(n)).collect::<Vec<_>>(), [12, 90, 3, 4]);
+        assert_eq!(transfer.get_arg(4), 0);
+    }
+
+    #[test]
+    fn unknown_commands_read_colon_separated_numbers() {
+        let script = TscScript::parse(b"#0001\r\n<ZZZ0001:0002rest");
+        assert_eq!(
+            script.events[0].tokens,
+            [
+                TscToken::Command { name: "ZZZ".into(), args: vec![1, 2] },
+                TscToken::Text(b"rest".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn to_bytes_round_trips() {
+        let text = b"#0100\r\n<MSGHi<NOD<END\r\n#0200\r\n<TRA0012:0090:0003:0004\r\n".to_vec();
+        assert_eq!(TscScript::parse(&text).to_bytes(), text);
+    }
+
+    #[test]
+    fn numbers_round_trip_beyond_four_digits() {
+        for value in [0, 7, 42, 999, 1000, 9999, 10000, 12345, u16::MAX] {
+            let mut bytes = vec![];
+            write_number(&mut bytes, value);
+
+            assert_eq!(bytes.len(), 4);
+            assert_eq!(read_number(&bytes), value);
+        }
+
+        assert_eq!(read_number(b"0123"), 123);
+        // 원본 스크립트처럼 천의 자리에 숫자가 아닌 글자가 있는 경우
+        assert_eq!(read_number(b":000"), 10000);
+    }
+}
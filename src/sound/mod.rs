@@ -0,0 +1,3 @@
+pub mod sound_effects;
+
+pub use sound_effects::*;
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use sdl2::mixer::{Channel, Chunk};
+
+use crate::constant::ASSET_DIR;
+
+/// 동시에 낼 수 있는 효과음 수
+pub const SOUND_CHANNELS: i32 = 16;
+
+/// 플레이어가 다쳤을 때
+pub const SOUND_PLAYER_HURT: u8 = 0x10;
+/// 플레이어가 죽었을 때
+pub const SOUND_PLAYER_DEATH: u8 = 0x11;
+/// 플레이어가 걸을 때
+pub const SOUND_FOOTSTEP: u8 = 0x18;
+
+/// 원본의 효과음 번호(npc.tbl 등)로 `Sound/<번호 16진수 2자리>.wav` 를 재생한다.
+/// 효과음 파일은 저장소에 들어있지 않다. 원본 게임(Doukutsu.exe)에서 뽑아낸 효과음을
+/// `resources/Sound/`에 번호 이름으로 넣어야 한다. 파일이 없는 효과음은 조용히 넘어간다.
+#[derive(Default)]
+pub struct SoundEffects {
+    chunks: HashMap<u8, Option<Chunk>>,
+    /// 오디오 장치를 열지 못했으면 아무 소리도 내지 않는다.
+    enabled: bool,
+}
+
+impl SoundEffects {
+    /// 믹서가 열려 있어야 한다.
+    pub fn new() -> Self {
+        sdl2::mixer::allocate_channels(SOUND_CHANNELS);
+        let dir = PathBuf::from(format!("{}Sound", ASSET_DIR));
+        let enabled = dir.is_dir();
+        if !enabled {
+            eprintln!("warning: {} not found, sound effects are disabled", dir.display());
+        }
+        SoundEffects { chunks: HashMap::new(), enabled }
+    }
+
+    /// 오디오 장치 없이 실행할 때. play는 아무것도 하지 않는다.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn get_path(id: u8) -> PathBuf {
+        PathBuf::from(format!("{}Sound/{:02x}.wav", ASSET_DIR, id))
+    }
+
+    /// 0번은 소리 없음이다.
+    pub fn play(&mut self, id: u8) {
+        if id == 0 || !self.enabled {
+            return;
+        }
+
+        let chunk =
+            self.chunks.entry(id).or_insert_with(|| Chunk::from_file(Self::get_path(id)).ok());
+        if let Some(chunk) = chunk {
+            let _ = Channel::all().play(chunk, 0);
+        }
+    }
+}
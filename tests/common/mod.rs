@@ -0,0 +1,21 @@
+// 테스트 파일마다 따로 컴파일되므로 쓰지 않는 함수가 있어도 경고하지 않는다.
+#![allow(dead_code)]
+
+use cavestory::game::Game;
+use cavestory::graphics::RecordingRenderer;
+
+/// 테스트에서 쓰는 난수 시드
+pub const SEED: u64 = 1;
+
+/// 첫 맵에서 게임을 시작한다.
+pub fn start_game(renderer: &mut RecordingRenderer) -> Game {
+    start_game_with(renderer, |_| {})
+}
+
+/// 첫 맵을 읽기 전에 setup으로 녹화나 재생 등을 설정하고 게임을 시작한다.
+pub fn start_game_with(renderer: &mut RecordingRenderer, setup: impl FnOnce(&mut Game)) -> Game {
+    let mut game = Game::with_seed(SEED);
+    setup(&mut game);
+    game.init_sprite(renderer).unwrap();
+    game
+}
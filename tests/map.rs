@@ -0,0 +1,49 @@
+use cavestory::game::GameState;
+use cavestory::graphics::RecordingRenderer;
+use cavestory::input::Input;
+
+mod common;
+use common::start_game;
+
+#[test]
+fn failed_map_change_keeps_current_map() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let position = game.player.as_ref().unwrap().get_position();
+
+    assert!(game.change_map("missing.tmx".into(), &mut renderer).is_err());
+    assert!(game.change_map("Missing".into(), &mut renderer).is_err());
+
+    assert_eq!(game.current_map, "stage.tmx");
+    assert!(game.level.contains_key("map"));
+    assert_eq!(game.player.as_ref().unwrap().get_position(), position);
+}
+
+#[test]
+fn retry_restarts_with_new_player() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+    let health = game.player.as_ref().unwrap().current_health;
+
+    game.player.as_mut().unwrap().current_health = 0;
+    game.state = GameState::Retry;
+    game.tick(&mut input, &mut renderer);
+
+    assert_eq!(game.state, GameState::Playing);
+    assert_eq!(game.player.as_ref().unwrap().current_health, health);
+}
+
+#[test]
+fn failed_retry_goes_back_to_game_over() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+
+    game.current_map = "missing.tmx".into();
+    game.state = GameState::Retry;
+    game.tick(&mut input, &mut renderer);
+
+    assert_eq!(game.state, GameState::GameOver);
+    assert!(game.player.is_some());
+}
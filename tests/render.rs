@@ -0,0 +1,83 @@
+use cavestory::game::Game;
+use cavestory::graphics::{DrawCommand, RecordingRenderer, Renderer, Transform};
+use cavestory::input::{Action, Input, InputSnapshot};
+use sdl2::rect::Rect;
+
+mod common;
+use common::start_game;
+
+fn draw_frame(game: &Game, renderer: &mut RecordingRenderer) {
+    renderer.clear();
+    game.render(renderer);
+    renderer.present();
+}
+
+fn player_copy(renderer: &RecordingRenderer) -> (Rect, Rect, Transform) {
+    match renderer.copies("player").as_slice() {
+        [DrawCommand::Copy { src, dest, transform, .. }] => (*src, *dest, *transform),
+        copies => panic!("expected one player sprite, got {:?}", copies),
+    }
+}
+
+fn player_dest(renderer: &RecordingRenderer) -> Rect {
+    player_copy(renderer).1
+}
+
+#[test]
+fn renders_map_player_and_hud() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+
+    for _ in 0..30 {
+        game.tick(&mut input, &mut renderer);
+    }
+    draw_frame(&game, &mut renderer);
+
+    assert_eq!(renderer.commands.first(), Some(&DrawCommand::Clear));
+    assert_eq!(renderer.commands.last(), Some(&DrawCommand::Present));
+    assert_eq!(renderer.frames, 1);
+
+    // 맵의 타일이 그려진다. 충돌 레이어의 타일셋은 그리지 않는다.
+    assert!(game.level["map"]
+        .textures
+        .values()
+        .any(|texture| !renderer.copies(texture).is_empty()));
+
+    // 멈춰 있는 플레이어는 카메라 기준 위치에 16x16으로 그려진다.
+    let (x, y) = game.player.as_ref().unwrap().get_position();
+    let camera = game.camera.get_rect();
+    assert_eq!(player_dest(&renderer), Rect::new(x - camera.x, y - camera.y, 16, 16));
+
+    // HUD의 체력 막대와 무기 칸
+    assert!(!renderer.copies("textbox").is_empty());
+    assert!(!renderer.copies("arms").is_empty());
+}
+
+#[test]
+fn player_sprite_follows_input() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+
+    for _ in 0..30 {
+        game.tick(&mut input, &mut renderer);
+    }
+    draw_frame(&game, &mut renderer);
+    let before = player_dest(&renderer);
+
+    for _ in 0..20 {
+        input.restore(&InputSnapshot { held: vec![Action::MoveRight], ..Default::default() });
+        game.tick(&mut input, &mut renderer);
+    }
+    draw_frame(&game, &mut renderer);
+    let after = player_dest(&renderer);
+
+    assert!(after.x() > before.x(), "{:?} -> {:?}", before, after);
+
+    // 오른쪽을 볼 때는 왼쪽을 보는 줄을 뒤집어 그린다.
+    let (src, _, transform) = player_copy(&renderer);
+    assert_eq!(src.y(), 0);
+    assert_eq!(transform, Transform::flip(true, false));
+    assert_eq!(renderer.frames, 2);
+}
@@ -0,0 +1,60 @@
+use cavestory::graphics::RecordingRenderer;
+use cavestory::input::{Action, Input, InputReplay, InputSnapshot};
+use rand::Rng;
+
+mod common;
+use common::{start_game_with, SEED};
+
+const TICKS: usize = 300;
+
+/// 오른쪽으로 걷다가 틈틈이 점프하고 총을 쏜다.
+fn scripted_input(tick: usize) -> InputSnapshot {
+    let mut snapshot = InputSnapshot::default();
+    if tick % 100 < 60 {
+        snapshot.held.push(Action::MoveRight);
+    } else {
+        snapshot.held.push(Action::MoveLeft);
+    }
+    if tick % 40 < 10 {
+        snapshot.held.push(Action::Jump);
+    }
+    if tick.is_multiple_of(15) {
+        snapshot.pressed.push(Action::Shoot);
+    }
+    snapshot
+}
+
+#[test]
+fn replay_reproduces_recorded_game() {
+    let mut renderer = RecordingRenderer::new();
+    let mut input = Input::default();
+
+    let mut recorded = start_game_with(&mut renderer, |game| game.start_recording());
+    let start = recorded.player.as_ref().unwrap().get_position();
+    for tick in 0..TICKS {
+        input.restore(&scripted_input(tick));
+        recorded.tick(&mut input, &mut renderer);
+    }
+
+    let text = recorded.recorder.as_ref().unwrap().to_text();
+    let replay = InputReplay::parse(&text).unwrap();
+    assert_eq!(replay.seed, SEED);
+    assert_eq!(replay.frames, recorded.recorder.as_ref().unwrap().frames);
+
+    // 재생할 때는 키보드 입력을 무시한다.
+    let mut replayed = start_game_with(&mut renderer, |game| game.start_replay(replay));
+    let mut ignored = Input::default();
+    for _ in 0..TICKS {
+        ignored.restore(&InputSnapshot { held: vec![Action::MoveLeft], ..Default::default() });
+        replayed.tick(&mut ignored, &mut renderer);
+    }
+    assert!(replayed.replay.as_ref().unwrap().is_finished());
+
+    let (expected, actual) = (recorded.player.as_ref().unwrap(), replayed.player.as_ref().unwrap());
+    assert_ne!(expected.get_position(), start);
+    assert_eq!(actual.get_position(), expected.get_position());
+    assert_eq!(actual.current_health, expected.current_health);
+    assert_eq!(actual.bullets.len(), expected.bullets.len());
+    assert_eq!(replayed.state, recorded.state);
+    assert_eq!(replayed.rng.gen::<u64>(), recorded.rng.gen::<u64>());
+}
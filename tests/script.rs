@@ -0,0 +1,83 @@
+use cavestory::graphics::RecordingRenderer;
+use cavestory::input::{Action, Input, InputSnapshot};
+use cavestory::script::TscScript;
+use cavestory::GameResult;
+
+mod common;
+use common::start_game;
+
+#[test]
+fn tra_goes_to_stage_from_table() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+
+    game.script.load(TscScript::parse(b"#0100\n<TRA0012:0090:0010:0008\n"));
+    game.script.start_event(100);
+    assert!(matches!(game.update(20), GameResult::GotoMap(map) if map == "Cave"));
+}
+
+#[test]
+fn tra_loads_stage_and_runs_its_script() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+
+    game.script.load(TscScript::parse(b"#0100\n<TRA0012:0090:0010:0008\n"));
+    game.script.start_event(100);
+    game.tick(&mut input, &mut renderer);
+
+    assert_eq!(game.current_map, "Cave");
+    assert_eq!(game.player.as_ref().unwrap().get_position(), (10 * 16, 8 * 16));
+    // Stage/Cave.tsc의 #0090을 이어서 실행한다.
+    assert!(game.script.script.get_event(90).is_some());
+    assert!(game.script.is_running());
+}
+
+#[test]
+fn tra_to_unknown_stage_ends_event() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+
+    game.script.load(TscScript::parse(b"#0100\n<TRA9999:0090:0010:0008\n"));
+    game.script.start_event(100);
+    assert!(matches!(game.update(20), GameResult::None));
+    assert!(!game.script.is_running());
+}
+
+#[test]
+fn interacting_with_stage_entity_starts_its_event() {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+    let mut input = Input::default();
+
+    game.change_map("Cave".into(), &mut renderer).unwrap();
+    game.script.end();
+    let trigger = game.level["map"]
+        .triggers
+        .iter()
+        .find(|trigger| trigger.event == 101 && !trigger.on_touch)
+        .cloned()
+        .unwrap();
+
+    // 문 앞에 세우고 바닥에 닿을 때까지 기다린다.
+    let (x, y) = (trigger.position.left as i32, trigger.position.top as i32);
+    game.player.as_mut().unwrap().enter_map(x, y, None);
+    for _ in 0..30 {
+        game.tick(&mut input, &mut renderer);
+    }
+    assert!(!game.script.is_running());
+
+    input.restore(&InputSnapshot {
+        held: vec![Action::LookDown],
+        pressed: vec![Action::LookDown],
+        released: vec![],
+    });
+    game.tick(&mut input, &mut renderer);
+    assert!(game.script.is_running());
+
+    // #0101은 <WAI 없이 Start Point(13)로 이동한다.
+    for _ in 0..10 {
+        game.tick(&mut input, &mut renderer);
+    }
+    assert_eq!(game.current_map, "Start");
+}
@@ -0,0 +1,33 @@
+use cavestory::game::FPS;
+use cavestory::graphics::RecordingRenderer;
+use cavestory::input::{Action, Input, InputSnapshot};
+
+mod common;
+use common::start_game;
+
+/// elapsed를 frames 순서대로 넘겨 진행하고, 진행한 틱 수와 플레이어 위치를 반환한다.
+fn run(frames: &[u32]) -> (u32, (i32, i32)) {
+    let mut renderer = RecordingRenderer::new();
+    let mut game = start_game(&mut renderer);
+
+    let mut input = Input::default();
+    input.restore(&InputSnapshot { held: vec![Action::MoveRight], ..Default::default() });
+
+    let ticks =
+        frames.iter().map(|&elapsed| game.advance(elapsed, &mut input, &mut renderer)).sum();
+    (ticks, game.player.as_ref().unwrap().get_position())
+}
+
+#[test]
+fn same_ticks_give_same_position_regardless_of_frame_length() {
+    // 1초 동안 진행하면 정확히 FPS 틱이 된다.
+    let one_per_frame = run(&[17, 17, 16].repeat(FPS as usize / 3));
+    let three_per_frame = run(&[50; FPS as usize / 3]);
+    // 틱보다 짧거나 길어서 나머지가 다음 프레임으로 넘어가는 경우
+    let uneven = run(&[9, 33, 8].repeat(FPS as usize / 3));
+
+    assert_eq!(one_per_frame.0, FPS);
+    assert_ne!(one_per_frame.1, run(&[]).1);
+    assert_eq!(three_per_frame, one_per_frame);
+    assert_eq!(uneven, one_per_frame);
+}